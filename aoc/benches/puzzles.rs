@@ -0,0 +1,23 @@
+use aoc::registry;
+use aoc_common::{Inputs, Part};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn puzzles(c: &mut Criterion) {
+    for puzzle in registry::puzzles() {
+        let Ok(input) = Inputs::for_day(puzzle.year, puzzle.day).read("input") else {
+            continue;
+        };
+        let Ok(parsed) = puzzle.parse(&input) else {
+            continue;
+        };
+        let mut group = c.benchmark_group(format!("{}-day-{:02}", puzzle.year, puzzle.day));
+        group.bench_function("parse", |b| b.iter(|| puzzle.parse(black_box(&input))));
+        for part in Part::ALL {
+            group.bench_function(format!("part{}", part), |b| b.iter(|| puzzle.solve_parsed(part, black_box(&parsed))));
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, puzzles);
+criterion_main!(benches);
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use aoc_common::{Error, Part, Puzzle, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Step {
+    Parse,
+    Solve(Part),
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Parse => f.pad("parse"),
+            Step::Solve(part) => f.pad(&format!("part{}", part)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Timing {
+    pub puzzle: Puzzle,
+    pub step: Step,
+    pub median: Duration,
+}
+
+impl Timing {
+    /// The key identifying this timing in a baseline file, such as `2023-day-05/part1`.
+    pub fn key(&self) -> String {
+        return format!("{}-day-{:02}/{}", self.puzzle.year, self.puzzle.day, self.step);
+    }
+}
+
+/// Median nanoseconds per timing key, as saved with `--save` and compared with `--baseline`.
+pub type Baseline = BTreeMap<String, u64>;
+
+/// The median duration of `samples` runs of `run`.
+pub fn median_time<T>(samples: usize, mut run: impl FnMut() -> T) -> Duration {
+    let mut durations = (0..samples.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(run());
+            start.elapsed()
+        })
+        .collect::<Vec<Duration>>();
+    durations.sort();
+    return durations[durations.len() / 2];
+}
+
+/// Times parsing `input`, then solving each part of `puzzle` on the parsed model.
+pub fn measure(puzzle: &Puzzle, input: &str, samples: usize) -> Result<Vec<Timing>> {
+    let parsed = puzzle.parse(input)?;
+    let mut timings = vec![Timing {
+        puzzle: *puzzle,
+        step: Step::Parse,
+        median: median_time(samples, || puzzle.parse(black_box(input))),
+    }];
+    for part in Part::ALL {
+        match puzzle.solve_parsed(part, &parsed) {
+            Err(Error::Unsolved(_)) => continue,
+            result => result?,
+        };
+        timings.push(Timing {
+            puzzle: *puzzle,
+            step: Step::Solve(part),
+            median: median_time(samples, || puzzle.solve_parsed(part, black_box(&parsed))),
+        });
+    }
+    return Ok(timings);
+}
+
+pub fn to_baseline(timings: &[Timing]) -> Baseline {
+    return timings
+        .iter()
+        .map(|timing| (timing.key(), timing.median.as_nanos() as u64))
+        .collect();
+}
+
+pub fn load_baseline(path: &Path) -> Result<Baseline> {
+    let contents = fs::read_to_string(path)?;
+    return serde_json::from_str(&contents)
+        .map_err(|error| Error::Invalid(format!("{}: {}", path.display(), error)));
+}
+
+pub fn save_baseline(path: &Path, timings: &[Timing]) -> Result<()> {
+    let contents = serde_json::to_string_pretty(&to_baseline(timings))
+        .map_err(|error| Error::Invalid(error.to_string()))?;
+    fs::write(path, contents + "\n")?;
+    return Ok(());
+}
+
+/// Renders the timings as a table, with the change against `baseline` when one is given.
+pub fn format_table(timings: &[Timing], baseline: Option<&Baseline>) -> String {
+    let mut table = format!("{:<12} {:<6} {:>12}", "puzzle", "step", "median");
+    if baseline.is_some() {
+        table += &format!(" {:>12} {:>8}", "baseline", "change");
+    }
+    table += "\n";
+
+    for timing in timings {
+        table += &format!("{:<12} {:<6} {:>12}", timing.puzzle.to_string(), timing.step, format_duration(timing.median));
+        if let Some(baseline) = baseline {
+            match baseline.get(&timing.key()) {
+                Some(&nanoseconds) => {
+                    let previous = Duration::from_nanos(nanoseconds);
+                    let change = (timing.median.as_secs_f64() / previous.as_secs_f64().max(1e-9) - 1.0) * 100.0;
+                    table += &format!(" {:>12} {:>+7.1}%", format_duration(previous), change);
+                }
+                None => table += &format!(" {:>12} {:>8}", "-", "-"),
+            }
+        }
+        table += "\n";
+    }
+    return table;
+}
+
+fn format_duration(duration: Duration) -> String {
+    let nanoseconds = duration.as_nanos();
+    return match nanoseconds {
+        0..=999 => format!("{} ns", nanoseconds),
+        1_000..=999_999 => format!("{:.1} µs", nanoseconds as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.1} ms", nanoseconds as f64 / 1e6),
+        _ => format!("{:.2} s", nanoseconds as f64 / 1e9),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn timing(step: Step, median: Duration) -> Timing {
+        return Timing { puzzle: registry::find(2023, 5).unwrap(), step, median };
+    }
+
+    #[test]
+    fn test_measure() {
+        let puzzle = registry::find(2023, 9).unwrap();
+        let timings = measure(&puzzle, "0 3 6 9 12 15\n1 3 6 10 15 21", 3).unwrap();
+        let keys = timings.iter().map(Timing::key).collect::<Vec<_>>();
+        assert_eq!(keys, ["2023-day-09/parse", "2023-day-09/part1", "2023-day-09/part2"]);
+        assert!(measure(&puzzle, "0 3 x", 3).is_err());
+    }
+
+    #[test]
+    fn test_format_table() {
+        let timings = [
+            timing(Step::Parse, Duration::from_micros(250)),
+            timing(Step::Solve(Part::Two), Duration::from_millis(30)),
+        ];
+        let baseline = Baseline::from([("2023-day-05/parse".to_string(), 500_000)]);
+        assert_eq!(
+            format_table(&timings, Some(&baseline)),
+            "puzzle       step         median     baseline   change\n\
+             2023 day 05  parse      250.0 µs     500.0 µs   -50.0%\n\
+             2023 day 05  part2       30.0 ms            -        -\n"
+        );
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let path = std::env::temp_dir().join(format!("aoc-baseline-{}.json", std::process::id()));
+        let timings = [timing(Step::Solve(Part::One), Duration::from_nanos(1234))];
+        save_baseline(&path, &timings).unwrap();
+        assert_eq!(load_baseline(&path).unwrap(), to_baseline(&timings));
+        fs::remove_file(&path).unwrap();
+    }
+}
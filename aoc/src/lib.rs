@@ -0,0 +1,25 @@
+pub mod bench;
+pub mod registry;
+pub mod report;
+pub mod scaffold;
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use aoc_common::input::trim_input;
+use aoc_common::Result;
+
+/// Reads puzzle input from `path`, or from stdin when `path` is `None` or `-`.
+pub fn read_input(path: Option<&Path>) -> Result<String> {
+    let contents = match path {
+        Some(path) if path != Path::new("-") => fs::read_to_string(path)?,
+        _ => {
+            let mut contents = String::new();
+            io::stdin().read_to_string(&mut contents)?;
+            contents
+        }
+    };
+    return Ok(trim_input(&contents).to_string());
+}
+
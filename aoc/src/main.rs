@@ -0,0 +1,422 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use aoc::report::{self, Format, Outcome};
+use aoc::{bench, registry, scaffold};
+use aoc_2023_day_01::decoder::Decoder;
+use aoc_2023_day_01::vocabulary::{Vocabulary, LANGUAGES};
+use aoc_2023_day_02::queries;
+use aoc_2023_day_02::{format_bag, Bag};
+use aoc_common::answers::{Answers, Status};
+use aoc_common::input::data_root;
+use aoc_common::{Answer, Error, Inputs, Part, Puzzle, Result};
+use clap::{Parser, Subcommand};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one puzzle, or every registered puzzle with --all
+    Run {
+        #[arg(required_unless_present = "all")]
+        year: Option<u16>,
+        #[arg(required_unless_present = "all")]
+        day: Option<u8>,
+        /// Only solve this part (1 or 2)
+        #[arg(long)]
+        part: Option<Part>,
+        /// Input file, or `-` for stdin
+        #[arg(long, conflicts_with_all = ["all", "name"])]
+        input: Option<PathBuf>,
+        /// Named input of the day, such as `example` or `example-part2`
+        #[arg(long, default_value = "input")]
+        name: String,
+        /// Solve every registered puzzle against its `input`
+        #[arg(long, conflicts_with_all = ["year", "day"])]
+        all: bool,
+    },
+    /// Check solutions against the recorded answers
+    Verify {
+        year: Option<u16>,
+        day: Option<u8>,
+    },
+    /// Solve every puzzle and report answers, statuses and timings
+    Report {
+        year: Option<u16>,
+        day: Option<u8>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Named input of each day to solve
+        #[arg(long, default_value = "input")]
+        name: String,
+    },
+    /// Time parsing and each part of every puzzle against its `input`
+    Bench {
+        year: Option<u16>,
+        day: Option<u8>,
+        /// Runs per step, the median is reported
+        #[arg(long, default_value_t = 10)]
+        samples: usize,
+        /// Compare against timings saved earlier with --save
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// Save these timings as a JSON baseline
+        #[arg(long)]
+        save: Option<PathBuf>,
+    },
+    /// Record a confirmed answer, solving the puzzle unless --answer is given
+    Record {
+        year: u16,
+        day: u8,
+        part: Part,
+        /// Named input of the day the answer belongs to
+        #[arg(long, default_value = "input")]
+        input: String,
+        #[arg(long)]
+        answer: Option<String>,
+        /// Replace an answer that is already recorded
+        #[arg(long)]
+        force: bool,
+    },
+    /// Create the crate for a new day and register it with the runner
+    New {
+        year: u16,
+        day: u8,
+    },
+    /// Check 2023 day 02 games against a bag of cubes
+    Cubes {
+        /// Input file, or `-` for stdin
+        #[arg(long, conflicts_with = "name")]
+        input: Option<PathBuf>,
+        /// Named input of the day, such as `example`
+        #[arg(long, default_value = "input")]
+        name: String,
+        /// The cubes of each color in the bag, such as `red=12,green=13,blue=14`
+        #[arg(long, value_parser = parse_bag, default_value = "red=12,green=13,blue=14")]
+        bag: Bag,
+        /// List the impossible games with the first draw the bag cannot hold
+        #[arg(long)]
+        infeasible: bool,
+        /// Show the smallest bag for each game and for all of them
+        #[arg(long)]
+        minimal: bool,
+        /// Find the bags of at most this many cubes that make the most games possible
+        #[arg(long)]
+        budget: Option<u32>,
+    },
+    /// Sum the calibration values of a document of any size, as 2023 day 01 reads them
+    Calibrate {
+        /// Document to read, or `-` for stdin
+        #[arg(default_value = "-")]
+        input: PathBuf,
+        /// A built-in vocabulary (digits, english, french, german or spanish), or a file of
+        /// `word = digit` lines
+        #[arg(long, default_value = "english")]
+        vocabulary: String,
+        /// Match words whatever the case of their letters
+        #[arg(long)]
+        ignore_case: bool,
+        /// Show every line with the digits chosen on it, reporting all lines without a digit
+        #[arg(long)]
+        explain: bool,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Run { year, day, part, input, name, all } => {
+            if all {
+                run_all(part, &name)
+            } else {
+                run(year.unwrap(), day.unwrap(), part, input, &name)
+            }
+        }
+        Command::Verify { year, day } => verify(year, day),
+        Command::Report { year, day, format, name } => run_report(year, day, format, &name),
+        Command::Bench { year, day, samples, baseline, save } => run_bench(year, day, samples, baseline, save),
+        Command::Record { year, day, part, input, answer, force } => {
+            record(year, day, part, &input, answer, force)
+        }
+        Command::New { year, day } => new_day(year, day),
+        Command::Cubes { input, name, bag, infeasible, minimal, budget } => {
+            cubes(input, &name, &bag, infeasible, minimal, budget)
+        }
+        Command::Calibrate { input, vocabulary, ignore_case, explain } => {
+            calibrate(&input, &vocabulary, ignore_case, explain)
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error.to_string().trim_end());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parts(part: Option<Part>) -> Vec<Part> {
+    return part.map_or(Part::ALL.to_vec(), |part| vec![part]);
+}
+
+fn solve(puzzle: &Puzzle, parts: &[Part], input: &str) -> Result<()> {
+    for &part in parts {
+        let answer = match puzzle.solve(part, input) {
+            Ok(answer) => answer,
+            Err(Error::Unsolved(_)) => {
+                println!("{} part {}: not solved yet", puzzle, part);
+                continue;
+            }
+            Err(Error::Parse(error)) => return Err(Error::Invalid(error.render(input))),
+            Err(error) => return Err(error),
+        };
+        println!("{} part {}: {}", puzzle, part, answer);
+    }
+    return Ok(());
+}
+
+fn find(year: u16, day: u8) -> Result<Puzzle> {
+    return registry::find(year, day)
+        .ok_or_else(|| Error::Invalid(format!("no solution registered for {} day {:02}", year, day)));
+}
+
+fn select(year: Option<u16>, day: Option<u8>) -> Vec<Puzzle> {
+    return registry::puzzles()
+        .into_iter()
+        .filter(|puzzle| year.is_none_or(|year| puzzle.year == year))
+        .filter(|puzzle| day.is_none_or(|day| puzzle.day == day))
+        .collect();
+}
+
+fn run(year: u16, day: u8, part: Option<Part>, input: Option<PathBuf>, name: &str) -> Result<()> {
+    let puzzle = find(year, day)?;
+    let input = match input {
+        Some(path) => aoc::read_input(Some(&path))?,
+        None => Inputs::for_day(year, day).read(name)?,
+    };
+    return solve(&puzzle, &parts(part), &input);
+}
+
+fn run_all(part: Option<Part>, name: &str) -> Result<()> {
+    for puzzle in registry::puzzles() {
+        let input = Inputs::for_day(puzzle.year, puzzle.day).read(name)?;
+        solve(&puzzle, &parts(part), &input)?;
+    }
+    return Ok(());
+}
+
+fn verify(year: Option<u16>, day: Option<u8>) -> Result<()> {
+    let answers = Answers::load_default()?;
+    let (mut mismatches, mut errors) = (0, 0);
+
+    for puzzle in select(year, day) {
+        let inputs = Inputs::for_day(puzzle.year, puzzle.day);
+        for part in Part::ALL {
+            let mut names = answers.inputs(puzzle.year, puzzle.day, part).into_keys().collect::<Vec<_>>();
+            if !names.iter().any(|name| name == "input") && inputs.exists("input") {
+                names.push("input".to_string());
+            }
+
+            for name in names {
+                let result = inputs.read(&name).and_then(|input| puzzle.solve(part, &input));
+                let report = match result {
+                    Ok(actual) => match answers.status(puzzle.year, puzzle.day, part, &name, actual) {
+                        Status::Pass(answer) => format!("pass ({})", answer),
+                        Status::Unknown(answer) => format!("unknown ({})", answer),
+                        Status::Fail { expected, actual } => {
+                            mismatches += 1;
+                            format!("FAIL (expected {}, got {})", expected, actual)
+                        }
+                    },
+                    Err(Error::Unsolved(_)) => "unsolved".to_string(),
+                    Err(error) => {
+                        errors += 1;
+                        format!("ERROR ({})", error)
+                    }
+                };
+                println!("{} part {} [{}]: {}", puzzle, part, name, report);
+            }
+        }
+    }
+
+    return failure_summary(mismatches, errors);
+}
+
+/// An error counting the parts that did not match their recorded answer and those that failed.
+fn failure_summary(mismatches: usize, errors: usize) -> Result<()> {
+    let mut counts = vec![];
+    if mismatches > 0 {
+        counts.push(format!("{} part(s) did not match their recorded answer", mismatches));
+    }
+    if errors > 0 {
+        counts.push(format!("{} part(s) failed with an error", errors));
+    }
+    if counts.is_empty() {
+        return Ok(());
+    }
+    return Err(Error::Invalid(counts.join(", ")));
+}
+
+fn run_report(year: Option<u16>, day: Option<u8>, format: Format, name: &str) -> Result<()> {
+    let entries = report::collect(&select(year, day), &Answers::load_default()?, name);
+    print!("{}", report::format(&entries, format));
+
+    let count = |outcome| entries.iter().filter(|entry| entry.outcome == outcome).count();
+    return failure_summary(count(Outcome::Fail), count(Outcome::Error));
+}
+
+fn run_bench(
+    year: Option<u16>,
+    day: Option<u8>,
+    samples: usize,
+    baseline: Option<PathBuf>,
+    save: Option<PathBuf>,
+) -> Result<()> {
+    let baseline = baseline.map(|path| bench::load_baseline(&path)).transpose()?;
+    let mut timings = vec![];
+    for puzzle in select(year, day) {
+        let inputs = Inputs::for_day(puzzle.year, puzzle.day);
+        if !inputs.exists("input") {
+            continue;
+        }
+        timings.extend(bench::measure(&puzzle, &inputs.read("input")?, samples)?);
+    }
+
+    print!("{}", bench::format_table(&timings, baseline.as_ref()));
+    if let Some(path) = save {
+        bench::save_baseline(&path, &timings)?;
+    }
+    return Ok(());
+}
+
+fn record(year: u16, day: u8, part: Part, input: &str, answer: Option<String>, force: bool) -> Result<()> {
+    let puzzle = find(year, day)?;
+    let answer = match answer {
+        Some(answer) => answer.parse::<i128>().map_or(Answer::Text(answer), Answer::Integer),
+        None => puzzle.solve(part, &Inputs::for_day(year, day).read(input)?)?,
+    };
+
+    let mut answers = Answers::load_default()?;
+    if let Some(existing) = answers.get(year, day, part, input) {
+        if existing != answer && !force {
+            return Err(Error::Invalid(format!(
+                "{} part {} [{}] already has answer {}, use --force to replace it",
+                puzzle, part, input, existing
+            )));
+        }
+    }
+    answers.record(year, day, part, input, &answer);
+    answers.save(year)?;
+    println!("{} part {} [{}]: recorded {}", puzzle, part, input, answer);
+    return Ok(());
+}
+
+fn new_day(year: u16, day: u8) -> Result<()> {
+    let workspace = scaffold::workspace_root();
+    for path in scaffold::create_day(&workspace, &data_root(), year, day)? {
+        println!("wrote {}", path.strip_prefix(&workspace).unwrap_or(&path).display());
+    }
+    return Ok(());
+}
+
+fn parse_bag(text: &str) -> std::result::Result<Bag, String> {
+    return aoc_2023_day_02::parse_bag(text).map_err(|error| error.to_string());
+}
+
+fn cubes(
+    input: Option<PathBuf>,
+    name: &str,
+    bag: &Bag,
+    infeasible: bool,
+    minimal: bool,
+    budget: Option<u32>,
+) -> Result<()> {
+    let input = match input {
+        Some(path) => aoc::read_input(Some(&path))?,
+        None => Inputs::for_day(2023, 2).read(name)?,
+    };
+    let games = aoc_2023_day_02::parse(&input).map_err(|error| Error::Invalid(error.render(&input)))?;
+    let possible = games.iter().filter(|game| aoc_2023_day_02::is_possible(game, bag)).count();
+    println!("{} of {} games are possible with {}", possible, games.len(), format_bag(bag));
+    println!("sum of their IDs: {}", aoc_2023_day_02::possible_id_sum(&games, bag));
+
+    if infeasible {
+        for violation in queries::infeasible_games(&games, bag) {
+            let excess = violation.excess.iter().map(|(color, drawn, held)| format!("{} {} of {}", color, drawn, held));
+            println!(
+                "game {} draw {}: {} ({})",
+                violation.game.id,
+                violation.draw_index + 1,
+                format_bag(violation.draw),
+                excess.collect::<Vec<_>>().join(", ")
+            );
+        }
+    }
+    if minimal {
+        for game in &games {
+            println!("game {}: {}", game.id, format_bag(&aoc_2023_day_02::minimal_bag(game)));
+        }
+        println!("all games: {}", format_bag(&queries::minimal_bag_of_all(&games)));
+    }
+    if let Some(budget) = budget {
+        let best = queries::best_bags(&games, budget);
+        println!("at most {} cubes make {} games possible with:", budget, best.possible_games);
+        for bag in &best.bags {
+            println!("  {}", format_bag(bag));
+        }
+    }
+    return Ok(());
+}
+
+fn calibrate(input: &Path, vocabulary: &str, ignore_case: bool, explain: bool) -> Result<()> {
+    let mut vocabulary = match vocabulary {
+        "digits" => Vocabulary::digits(),
+        name if Path::new(name).is_file() => Vocabulary::load(Path::new(name))?,
+        name => Vocabulary::builtin(name).ok_or_else(|| {
+            let languages = LANGUAGES.iter().map(|(language, _)| *language).collect::<Vec<_>>();
+            Error::Invalid(format!("no vocabulary file or language named `{}`, try digits, {}", name, languages.join(", ")))
+        })?,
+    };
+    if ignore_case {
+        vocabulary = vocabulary.ignoring_case()?;
+    }
+
+    let decoder = Decoder::new(&vocabulary);
+    let reader: Box<dyn BufRead> = if input == Path::new("-") {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(input)?))
+    };
+    if !explain {
+        println!("{}", decoder.sum_reader(reader)?);
+        return Ok(());
+    }
+
+    let (mut total, mut failures) = (0, 0);
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        match decoder.explain(index + 1, line) {
+            Ok(explanation) => {
+                print!("{}", explanation.render(line));
+                total += u64::from(explanation.value());
+            }
+            Err(error) => {
+                println!("error: {}", error);
+                failures += 1;
+            }
+        }
+    }
+    println!("total: {}", total);
+    if failures > 0 {
+        return Err(Error::Invalid(format!("{} line(s) have no digit", failures)));
+    }
+    return Ok(());
+}
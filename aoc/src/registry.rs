@@ -0,0 +1,38 @@
+use aoc_common::Puzzle;
+
+/// Every solved puzzle, ordered by year and day.
+pub fn puzzles() -> Vec<Puzzle> {
+    return vec![
+        Puzzle::of::<aoc_2022_day_01::Day01>(),
+        Puzzle::of::<aoc_2023_day_01::Day01>(),
+        Puzzle::of::<aoc_2023_day_02::Day02>(),
+        Puzzle::of::<aoc_2023_day_03::Day03>(),
+        Puzzle::of::<aoc_2023_day_04::Day04>(),
+        Puzzle::of::<aoc_2023_day_05::Day05>(),
+        Puzzle::of::<aoc_2023_day_06::Day06>(),
+        Puzzle::of::<aoc_2023_day_07::Day07>(),
+        Puzzle::of::<aoc_2023_day_08::Day08>(),
+        Puzzle::of::<aoc_2023_day_09::Day09>(),
+    ];
+}
+
+pub fn find(year: u16, day: u8) -> Option<Puzzle> {
+    return puzzles().into_iter().find(|puzzle| puzzle.year == year && puzzle.day == day);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puzzles_are_sorted_and_unique() {
+        let keys = puzzles().iter().map(|puzzle| (puzzle.year, puzzle.day)).collect::<Vec<_>>();
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(2023, 5).unwrap().to_string(), "2023 day 05");
+        assert!(find(2023, 25).is_none());
+    }
+}
@@ -0,0 +1,291 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use aoc_common::answers::{Answers, Status};
+use aoc_common::{Answer, Error, Inputs, Part, Puzzle};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Junit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail,
+    Unknown,
+    /// The part is not solved yet.
+    Unsolved,
+    Error,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass => f.pad("pass"),
+            Outcome::Fail => f.pad("fail"),
+            Outcome::Unknown => f.pad("unknown"),
+            Outcome::Unsolved => f.pad("unsolved"),
+            Outcome::Error => f.pad("error"),
+        }
+    }
+}
+
+/// The result of solving one part of one puzzle against a named input.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub puzzle: Puzzle,
+    pub part: Part,
+    pub input: String,
+    pub answer: Option<Answer>,
+    pub expected: Option<Answer>,
+    pub outcome: Outcome,
+    /// Time spent parsing the input, shared by both parts of a puzzle.
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    pub error: Option<String>,
+}
+
+/// Parses the `name` input of each puzzle once and solves every part, comparing against `answers`.
+///
+/// Puzzles without that input are left out.
+pub fn collect(puzzles: &[Puzzle], answers: &Answers, name: &str) -> Vec<Entry> {
+    let mut entries = vec![];
+    for puzzle in puzzles {
+        let inputs = Inputs::for_day(puzzle.year, puzzle.day);
+        if !inputs.exists(name) {
+            continue;
+        }
+
+        let start = Instant::now();
+        let parsed = inputs.read(name).and_then(|input| puzzle.parse(&input));
+        let parse_time = start.elapsed();
+
+        for part in Part::ALL {
+            let expected = answers.get(puzzle.year, puzzle.day, part, name);
+            let start = Instant::now();
+            let result = match &parsed {
+                Ok(parsed) => puzzle.solve_parsed(part, parsed),
+                Err(error) => Err(Error::Invalid(error.to_string())),
+            };
+            let solve_time = start.elapsed();
+
+            let (answer, outcome, error) = match result {
+                Ok(actual) => match answers.status(puzzle.year, puzzle.day, part, name, actual.clone()) {
+                    Status::Pass(_) => (Some(actual), Outcome::Pass, None),
+                    Status::Fail { .. } => (Some(actual), Outcome::Fail, None),
+                    Status::Unknown(_) => (Some(actual), Outcome::Unknown, None),
+                },
+                Err(Error::Unsolved(_)) => (None, Outcome::Unsolved, None),
+                Err(error) => (None, Outcome::Error, Some(error.to_string())),
+            };
+            entries.push(Entry {
+                puzzle: *puzzle,
+                part,
+                input: name.to_string(),
+                answer,
+                expected,
+                outcome,
+                parse_time,
+                solve_time,
+                error,
+            });
+        }
+    }
+    return entries;
+}
+
+pub fn format(entries: &[Entry], format: Format) -> String {
+    match format {
+        Format::Text => format_text(entries),
+        Format::Json => format_json(entries),
+        Format::Junit => format_junit(entries),
+    }
+}
+
+pub fn format_text(entries: &[Entry]) -> String {
+    let mut text = String::new();
+    for entry in entries {
+        let answer = entry.answer.as_ref().map_or("-".to_string(), Answer::to_string);
+        text += &format!("{} part {} [{}]: {:<7} {}", entry.puzzle, entry.part, entry.input, entry.outcome, answer);
+        match (&entry.outcome, &entry.expected, &entry.error) {
+            (Outcome::Fail, Some(expected), _) => text += &format!(" (expected {})", expected),
+            (Outcome::Error, _, Some(error)) => text += &format!(" ({})", error.lines().next().unwrap_or_default()),
+            _ => {}
+        }
+        text += "\n";
+    }
+    return text;
+}
+
+/// One JSON object per entry, in an array.
+pub fn format_json(entries: &[Entry]) -> String {
+    let objects = entries
+        .iter()
+        .map(|entry| {
+            return json!({
+                "year": entry.puzzle.year,
+                "day": entry.puzzle.day,
+                "part": entry.part.number(),
+                "input": entry.input,
+                "answer": entry.answer.as_ref().map(answer_value),
+                "expected": entry.expected.as_ref().map(answer_value),
+                "status": entry.outcome.to_string(),
+                "parse_time_ns": entry.parse_time.as_nanos() as u64,
+                "solve_time_ns": entry.solve_time.as_nanos() as u64,
+                "error": entry.error,
+            });
+        })
+        .collect::<Vec<Value>>();
+    return serde_json::to_string_pretty(&objects).unwrap() + "\n";
+}
+
+/// A JUnit XML document with one test suite per year and one test case per part.
+pub fn format_junit(entries: &[Entry]) -> String {
+    let mut years = entries.iter().map(|entry| entry.puzzle.year).collect::<Vec<u16>>();
+    years.dedup();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"aoc\">\n");
+    for year in years {
+        let suite = entries.iter().filter(|entry| entry.puzzle.year == year).collect::<Vec<&Entry>>();
+        let count = |outcome| suite.iter().filter(|entry| entry.outcome == outcome).count();
+        let time = suite.iter().map(|entry| entry.solve_time).sum::<Duration>()
+            + suite.iter().step_by(Part::ALL.len()).map(|entry| entry.parse_time).sum::<Duration>();
+        xml += &format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.6}\">\n",
+            year,
+            suite.len(),
+            count(Outcome::Fail),
+            count(Outcome::Error),
+            count(Outcome::Unknown) + count(Outcome::Unsolved),
+            time.as_secs_f64()
+        );
+        for entry in suite {
+            xml += &format!(
+                "    <testcase classname=\"{}.day-{:02}\" name=\"part{} [{}]\" time=\"{:.6}\">\n",
+                entry.puzzle.year,
+                entry.puzzle.day,
+                entry.part,
+                escape_xml(&entry.input),
+                entry.solve_time.as_secs_f64()
+            );
+            xml += "      <properties>\n";
+            let properties = [
+                ("answer", entry.answer.as_ref().map(Answer::to_string)),
+                ("expected", entry.expected.as_ref().map(Answer::to_string)),
+                ("status", Some(entry.outcome.to_string())),
+                ("parse_time_ns", Some(entry.parse_time.as_nanos().to_string())),
+                ("solve_time_ns", Some(entry.solve_time.as_nanos().to_string())),
+            ];
+            for (name, value) in properties {
+                if let Some(value) = value {
+                    xml += &format!("        <property name=\"{}\" value=\"{}\"/>\n", name, escape_xml(&value));
+                }
+            }
+            xml += "      </properties>\n";
+            match entry.outcome {
+                Outcome::Pass => {}
+                Outcome::Fail => {
+                    let message = format!(
+                        "expected {}, got {}",
+                        entry.expected.as_ref().unwrap(),
+                        entry.answer.as_ref().unwrap()
+                    );
+                    xml += &format!("      <failure message=\"{}\"/>\n", escape_xml(&message));
+                }
+                Outcome::Unknown => xml += "      <skipped message=\"no recorded answer\"/>\n",
+                Outcome::Unsolved => xml += "      <skipped message=\"not solved yet\"/>\n",
+                Outcome::Error => {
+                    let error = entry.error.as_deref().unwrap_or_default();
+                    let message = error.lines().next().unwrap_or_default();
+                    xml += &format!("      <error message=\"{}\">{}</error>\n", escape_xml(message), escape_xml(error));
+                }
+            }
+            xml += "    </testcase>\n";
+        }
+        xml += "  </testsuite>\n";
+    }
+    xml += "</testsuites>\n";
+    return xml;
+}
+
+/// Integer answers as JSON numbers when they fit, everything else as strings.
+fn answer_value(answer: &Answer) -> Value {
+    match answer {
+        Answer::Integer(value) => i64::try_from(*value).map_or(json!(value.to_string()), |value| json!(value)),
+        Answer::Text(value) => json!(value),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    return text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn entry(part: Part, answer: Option<i128>, expected: Option<i128>, outcome: Outcome) -> Entry {
+        return Entry {
+            puzzle: registry::find(2023, 6).unwrap(),
+            part,
+            input: "input".to_string(),
+            answer: answer.map(Answer::Integer),
+            expected: expected.map(Answer::Integer),
+            outcome,
+            parse_time: Duration::from_micros(3),
+            solve_time: Duration::from_micros(2),
+            error: None,
+        };
+    }
+
+    #[test]
+    fn test_collect() {
+        let puzzles = [registry::find(2023, 9).unwrap()];
+        let entries = collect(&puzzles, &Answers::load_default().unwrap(), "example");
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|entry| entry.outcome == Outcome::Pass));
+        assert_eq!(entries[0].answer, Some(Answer::Integer(114)));
+        assert!(collect(&puzzles, &Answers::default(), "missing").is_empty());
+    }
+
+    #[test]
+    fn test_format_json() {
+        let entries = [entry(Part::One, Some(1_000_000_000_000_000_000_000), None, Outcome::Unknown)];
+        let value = serde_json::from_str::<Value>(&format_json(&entries)).unwrap();
+        assert_eq!(
+            value,
+            json!([{
+                "year": 2023, "day": 6, "part": 1, "input": "input",
+                "answer": "1000000000000000000000", "expected": null, "status": "unknown",
+                "parse_time_ns": 3000, "solve_time_ns": 2000, "error": null,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_format_junit() {
+        let mut error = entry(Part::Two, None, Some(3), Outcome::Error);
+        error.error = Some("line 1: expected <a number>".to_string());
+        let xml = format_junit(&[entry(Part::One, Some(2), Some(1), Outcome::Fail), error]);
+        assert!(xml.contains("<testsuite name=\"2023\" tests=\"2\" failures=\"1\" errors=\"1\" skipped=\"0\" time=\"0.000007\">"));
+        assert!(xml.contains("<testcase classname=\"2023.day-06\" name=\"part1 [input]\" time=\"0.000002\">"));
+        assert!(xml.contains("<failure message=\"expected 1, got 2\"/>"));
+        assert!(xml.contains("<error message=\"line 1: expected &lt;a number&gt;\">"));
+        assert!(!xml.contains("name=\"answer\" value=\"\""));
+
+        let xml = format_junit(&[entry(Part::One, None, None, Outcome::Unsolved)]);
+        assert!(xml.contains("failures=\"0\" errors=\"0\" skipped=\"1\""));
+        assert!(xml.contains("<skipped message=\"not solved yet\"/>"));
+    }
+}
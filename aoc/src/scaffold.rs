@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aoc_common::{Error, Inputs, Result};
+
+const CARGO_TEMPLATE: &str = include_str!("../templates/Cargo.toml.tmpl");
+const LIB_TEMPLATE: &str = include_str!("../templates/lib.rs.tmpl");
+
+/// The workspace holding the `aoc` crate and every day crate.
+pub fn workspace_root() -> PathBuf {
+    return Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf();
+}
+
+/// Creates the `year-YYYY/day-DD` crate for a new day and registers it with the workspace and
+/// the `aoc` runner, returning every file written.
+///
+/// Empty `example` and `input` files are created under `data_root` unless they already exist.
+pub fn create_day(workspace: &Path, data_root: &Path, year: u16, day: u8) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::Invalid(format!("day {} is not between 1 and 25", day)));
+    }
+    let package = format!("aoc-{}-day-{:02}", year, day);
+    let relative_directory = format!("year-{}/day-{:02}", year, day);
+    let directory = workspace.join(&relative_directory);
+    if directory.exists() {
+        return Err(Error::Invalid(format!("{} already exists", directory.display())));
+    }
+
+    let render = |template: &str| {
+        return template
+            .replace("{year}", &year.to_string())
+            .replace("{day_number}", &day.to_string())
+            .replace("{day}", &format!("{:02}", day));
+    };
+    fs::create_dir_all(directory.join("src"))?;
+    fs::write(directory.join("Cargo.toml"), render(CARGO_TEMPLATE))?;
+    fs::write(directory.join("src/lib.rs"), render(LIB_TEMPLATE))?;
+    let mut written = vec![directory.join("Cargo.toml"), directory.join("src/lib.rs")];
+
+    let inputs = Inputs::new(data_root, year, day);
+    fs::create_dir_all(inputs.directory())?;
+    for name in ["example", "input"] {
+        if !inputs.exists(name) {
+            let path = inputs.directory().join(format!("{}.txt", name));
+            fs::write(&path, "")?;
+            written.push(path);
+        }
+    }
+
+    let manifest = workspace.join("Cargo.toml");
+    let mut contents = fs::read_to_string(&manifest)?;
+    contents = insert_sorted(&contents, &format!("    \"year-{}/day-*\",", year), |line| {
+        return line.starts_with("    \"year-") && line.ends_with("/day-*\",");
+    });
+    contents = insert_sorted(&contents, &format!("{} = {{ path = \"{}\" }}", package, relative_directory), |line| {
+        return is_day_package(line);
+    });
+    fs::write(&manifest, contents)?;
+    written.push(manifest);
+
+    let manifest = workspace.join("aoc/Cargo.toml");
+    let contents = fs::read_to_string(&manifest)?;
+    fs::write(&manifest, insert_sorted(&contents, &format!("{}.workspace = true", package), is_day_package))?;
+    written.push(manifest);
+
+    let registry = workspace.join("aoc/src/registry.rs");
+    let contents = fs::read_to_string(&registry)?;
+    let entry = format!("        Puzzle::of::<{}::Day{:02}>(),", package.replace('-', "_"), day);
+    fs::write(&registry, insert_sorted(&contents, &entry, |line| line.starts_with("        Puzzle::of::<aoc_")))?;
+    written.push(registry);
+
+    return Ok(written);
+}
+
+fn is_day_package(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix("aoc-") else {
+        return false;
+    };
+    return rest.starts_with(|c: char| c.is_ascii_digit());
+}
+
+/// Inserts `line` among the consecutive lines matching `is_entry`, keeping them sorted.
+fn insert_sorted(contents: &str, line: &str, is_entry: impl Fn(&str) -> bool) -> String {
+    let mut lines = contents.lines().collect::<Vec<&str>>();
+    if lines.contains(&line) {
+        return contents.to_string();
+    }
+    let entries = lines.iter().enumerate().filter(|(_, existing)| is_entry(existing)).map(|(index, _)| index);
+    let position = entries
+        .clone()
+        .find(|&index| lines[index] > line)
+        .or_else(|| entries.last().map(|index| index + 1))
+        .unwrap_or(lines.len());
+    lines.insert(position, line);
+    return lines.join("\n") + "\n";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A workspace with day 09 of 2023, in a directory removed when the `TempDir` drops.
+    fn temporary_workspace() -> TempDir {
+        let workspace = TempDir::new().unwrap();
+        let root = workspace.path();
+        fs::create_dir_all(root.join("aoc/src")).unwrap();
+        fs::create_dir_all(root.join("year-2023/day-09")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"year-2023/day-*\",\n]\n\n[workspace.dependencies]\n\
+             aoc-2023-day-09 = { path = \"year-2023/day-09\" }\nclap = \"4\"\n",
+        )
+        .unwrap();
+        fs::write(root.join("aoc/Cargo.toml"), "[dependencies]\naoc-common.workspace = true\naoc-2023-day-09.workspace = true\n").unwrap();
+        fs::write(
+            root.join("aoc/src/registry.rs"),
+            "    return vec![\n        Puzzle::of::<aoc_2023_day_09::Day09>(),\n    ];\n",
+        )
+        .unwrap();
+        return workspace;
+    }
+
+    #[test]
+    fn test_create_day() {
+        let workspace = temporary_workspace();
+        let root = workspace.path();
+        create_day(root, root, 2023, 10).unwrap();
+        create_day(root, root, 2022, 3).unwrap();
+
+        let lib = fs::read_to_string(root.join("year-2023/day-10/src/lib.rs")).unwrap();
+        assert!(lib.contains("impl Solution for Day10 {\n    const YEAR: u16 = 2023;\n    const DAY: u8 = 10;"));
+        assert!(lib.contains("answer_tests!(Day10, ignore = \"no answers recorded yet\");"));
+        assert!(fs::read_to_string(root.join("year-2023/day-10/Cargo.toml")).unwrap().contains("name = \"aoc-2023-day-10\""));
+        assert_eq!(fs::read_to_string(root.join("year-2022/day-03/input.txt")).unwrap(), "");
+        assert!(root.join("year-2022/day-03/example.txt").is_file());
+
+        assert_eq!(
+            fs::read_to_string(root.join("Cargo.toml")).unwrap(),
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"year-2022/day-*\",\n    \"year-2023/day-*\",\n]\n\n\
+             [workspace.dependencies]\naoc-2022-day-03 = { path = \"year-2022/day-03\" }\n\
+             aoc-2023-day-09 = { path = \"year-2023/day-09\" }\naoc-2023-day-10 = { path = \"year-2023/day-10\" }\nclap = \"4\"\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("aoc/Cargo.toml")).unwrap(),
+            "[dependencies]\naoc-common.workspace = true\naoc-2022-day-03.workspace = true\n\
+             aoc-2023-day-09.workspace = true\naoc-2023-day-10.workspace = true\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("aoc/src/registry.rs")).unwrap(),
+            "    return vec![\n        Puzzle::of::<aoc_2022_day_03::Day03>(),\n        Puzzle::of::<aoc_2023_day_09::Day09>(),\n\
+             \x20       Puzzle::of::<aoc_2023_day_10::Day10>(),\n    ];\n"
+        );
+    }
+
+    #[test]
+    fn test_refuse_existing_day() {
+        let workspace = temporary_workspace();
+        let root = workspace.path();
+        fs::create_dir_all(root.join("year-2023/day-09/src")).unwrap();
+        fs::write(root.join("year-2023/day-09/src/lib.rs"), "// solved\n").unwrap();
+
+        assert!(create_day(root, root, 2023, 9).is_err());
+        assert!(create_day(root, root, 2023, 26).is_err());
+        assert_eq!(fs::read_to_string(root.join("year-2023/day-09/src/lib.rs")).unwrap(), "// solved\n");
+    }
+}
@@ -0,0 +1,71 @@
+use std::fmt;
+
+/// The result of solving one part of a puzzle, whatever its underlying type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+}
+
+macro_rules! impl_from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Answer {
+                fn from(value: $integer) -> Self {
+                    return Answer::Integer(value as i128);
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+/// Values past `i128::MAX` are kept exactly, as text.
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        return i128::try_from(value).map_or_else(|_| Answer::Text(value.to_string()), Answer::Integer);
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        return Answer::Text(value);
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        return Answer::Text(value.to_string());
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_integers() {
+        assert_eq!(Answer::from(54388_u32), Answer::Integer(54388));
+        assert_eq!(Answer::from(-3_i32), Answer::Integer(-3));
+        assert_eq!(Answer::from(13129439557681_i64), Answer::Integer(13129439557681));
+        assert_eq!(Answer::from(12263631_usize), Answer::Integer(12263631));
+        assert_eq!(Answer::from(43663323_u128), Answer::Integer(43663323));
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(251346198_i64).to_string(), "251346198");
+        assert_eq!(Answer::from("EXAMPLE").to_string(), "EXAMPLE");
+    }
+}
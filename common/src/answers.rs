@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::input::{data_root, Inputs};
+use crate::{Answer, Error, Part, Result, Solution};
+
+type Entries = BTreeMap<String, BTreeMap<String, BTreeMap<String, toml::Value>>>;
+
+/// Confirmed puzzle answers, stored as one TOML file per year:
+///
+/// ```toml
+/// [day-05.part1]
+/// example = 35
+/// input = 251346198
+/// ```
+///
+/// Each key under a part is the name of the input the answer belongs to, as understood by
+/// [`Inputs`].
+#[derive(Debug, Default)]
+pub struct Answers {
+    directory: PathBuf,
+    years: BTreeMap<u16, Entries>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass(Answer),
+    Fail { expected: Answer, actual: Answer },
+    Unknown(Answer),
+}
+
+impl Answers {
+    pub fn default_directory() -> PathBuf {
+        return data_root().join("answers");
+    }
+
+    pub fn load_default() -> Result<Answers> {
+        return Answers::load(&Answers::default_directory());
+    }
+
+    pub fn load(directory: &Path) -> Result<Answers> {
+        let mut answers = Answers {
+            directory: directory.to_path_buf(),
+            years: BTreeMap::new(),
+        };
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            let year = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u16>().ok());
+            if let (Some(year), Some("toml")) = (year, path.extension().and_then(|e| e.to_str())) {
+                let contents = fs::read_to_string(&path)?;
+                let entries = toml::from_str::<Entries>(&contents)
+                    .map_err(|error| Error::Invalid(format!("{}: {}", path.display(), error)))?;
+                answers.years.insert(year, entries);
+            }
+        }
+        return Ok(answers);
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: Part, input: &str) -> Option<Answer> {
+        return self
+            .years
+            .get(&year)?
+            .get(&day_key(day))?
+            .get(&part_key(part))?
+            .get(input)
+            .map(from_value);
+    }
+
+    /// The recorded answers for one part, keyed by input name.
+    pub fn inputs(&self, year: u16, day: u8, part: Part) -> BTreeMap<String, Answer> {
+        return self
+            .years
+            .get(&year)
+            .and_then(|entries| entries.get(&day_key(day)))
+            .and_then(|parts| parts.get(&part_key(part)))
+            .map(|inputs| {
+                inputs
+                    .iter()
+                    .map(|(name, value)| (name.clone(), from_value(value)))
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    pub fn record(&mut self, year: u16, day: u8, part: Part, input: &str, answer: &Answer) {
+        self.years
+            .entry(year)
+            .or_default()
+            .entry(day_key(day))
+            .or_default()
+            .entry(part_key(part))
+            .or_default()
+            .insert(input.to_string(), to_value(answer));
+    }
+
+    pub fn save(&self, year: u16) -> Result<()> {
+        let entries = self.years.get(&year).cloned().unwrap_or_default();
+        let contents = toml::to_string(&entries)
+            .map_err(|error| Error::Invalid(error.to_string()))?;
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.directory.join(format!("{}.toml", year)), contents)?;
+        return Ok(());
+    }
+
+    pub fn status(&self, year: u16, day: u8, part: Part, input: &str, actual: Answer) -> Status {
+        match self.get(year, day, part, input) {
+            Some(expected) if expected.to_string() == actual.to_string() => Status::Pass(actual),
+            Some(expected) => Status::Fail { expected, actual },
+            None => Status::Unknown(actual),
+        }
+    }
+}
+
+/// Solves `part` of `S` against every input with a recorded answer, panicking on any mismatch.
+pub fn assert_recorded<S: Solution>(part: Part) {
+    let answers = Answers::load_default().unwrap();
+    let recorded = answers.inputs(S::YEAR, S::DAY, part);
+    assert!(!recorded.is_empty(), "no recorded answers for {} day {:02} part {}", S::YEAR, S::DAY, part);
+
+    let inputs = Inputs::for_day(S::YEAR, S::DAY);
+    for (name, expected) in recorded {
+        let model = S::parse(&inputs.read(&name).unwrap()).unwrap();
+        let actual = match part {
+            Part::One => S::part1(&model),
+            Part::Two => S::part2(&model),
+        };
+        assert_eq!(actual.unwrap().to_string(), expected.to_string(), "{} input", name);
+    }
+}
+
+/// Generates one `#[test]` per part checking a [`Solution`] against its recorded answers.
+///
+/// With `ignore = "reason"` the tests are ignored, for days whose answers are not recorded yet.
+#[macro_export]
+macro_rules! answer_tests {
+    ($solution:ty, ignore = $reason:literal) => {
+        #[test]
+        #[ignore = $reason]
+        fn test_part1() {
+            $crate::answers::assert_recorded::<$solution>($crate::Part::One);
+        }
+
+        #[test]
+        #[ignore = $reason]
+        fn test_part2() {
+            $crate::answers::assert_recorded::<$solution>($crate::Part::Two);
+        }
+    };
+    ($solution:ty) => {
+        #[test]
+        fn test_part1() {
+            $crate::answers::assert_recorded::<$solution>($crate::Part::One);
+        }
+
+        #[test]
+        fn test_part2() {
+            $crate::answers::assert_recorded::<$solution>($crate::Part::Two);
+        }
+    };
+}
+
+fn day_key(day: u8) -> String {
+    return format!("day-{:02}", day);
+}
+
+fn part_key(part: Part) -> String {
+    return format!("part{}", part);
+}
+
+fn from_value(value: &toml::Value) -> Answer {
+    match value {
+        toml::Value::Integer(value) => Answer::Integer(*value as i128),
+        toml::Value::String(value) => Answer::Text(value.clone()),
+        other => Answer::Text(other.to_string()),
+    }
+}
+
+fn to_value(answer: &Answer) -> toml::Value {
+    match answer {
+        Answer::Integer(value) => match i64::try_from(*value) {
+            Ok(value) => toml::Value::Integer(value),
+            Err(_) => toml::Value::String(value.to_string()),
+        },
+        Answer::Text(value) => toml::Value::String(value.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_default() {
+        let answers = Answers::load_default().unwrap();
+        assert_eq!(answers.get(2023, 5, Part::One, "input"), Some(Answer::Integer(251346198)));
+        assert_eq!(answers.get(2023, 8, Part::Two, "input"), Some(Answer::Integer(13129439557681)));
+        assert_eq!(answers.get(2023, 25, Part::One, "input"), None);
+    }
+
+    #[test]
+    fn test_record_and_save() {
+        let directory = TempDir::new().unwrap();
+        let mut answers = Answers::load(directory.path()).unwrap();
+        answers.record(2015, 1, Part::One, "input", &Answer::Integer(74));
+        answers.record(2015, 1, Part::Two, "input", &Answer::from("ABC"));
+        answers.save(2015).unwrap();
+
+        let reloaded = Answers::load(directory.path()).unwrap();
+        assert_eq!(reloaded.get(2015, 1, Part::One, "input"), Some(Answer::Integer(74)));
+        assert_eq!(reloaded.get(2015, 1, Part::Two, "input"), Some(Answer::from("ABC")));
+    }
+
+    #[test]
+    fn test_status() {
+        let mut answers = Answers::default();
+        answers.record(2015, 1, Part::One, "input", &Answer::Integer(74));
+        assert_eq!(
+            answers.status(2015, 1, Part::One, "input", Answer::Integer(74)),
+            Status::Pass(Answer::Integer(74))
+        );
+        assert_eq!(
+            answers.status(2015, 1, Part::One, "input", Answer::Integer(75)),
+            Status::Fail { expected: Answer::Integer(74), actual: Answer::Integer(75) }
+        );
+        assert_eq!(
+            answers.status(2015, 1, Part::Two, "input", Answer::Integer(1)),
+            Status::Unknown(Answer::Integer(1))
+        );
+    }
+}
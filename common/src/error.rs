@@ -0,0 +1,47 @@
+use std::{fmt, io};
+
+use crate::{ParseError, Part};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse(ParseError),
+    Invalid(String),
+    /// A part whose solution is not written yet, as in freshly scaffolded days.
+    Unsolved(Part),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{}", error),
+            Error::Parse(error) => write!(f, "{}", error),
+            Error::Invalid(message) => write!(f, "{}", message),
+            Error::Unsolved(part) => write!(f, "part {} is not solved yet", part),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Parse(error) => Some(error),
+            Error::Invalid(_) | Error::Unsolved(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        return Error::Io(error);
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        return Error::Parse(error);
+    }
+}
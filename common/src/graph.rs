@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+use crate::math::{self, Congruence};
+use crate::{Error, Result};
+
+/// A walk through a finite state space where every state has exactly one successor: the first
+/// `tail` states are visited once, and the `length` states after them repeat forever.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<S> {
+    /// Every state up to the first repeat, starting with the start state.
+    pub states: Vec<S>,
+    pub tail: usize,
+    pub length: usize,
+}
+
+impl<S: Clone + Eq + Hash> Cycle<S> {
+    /// Walks from `start` with `step` until a state repeats.
+    pub fn find(start: S, mut step: impl FnMut(&S) -> S) -> Self {
+        let mut first_visits = HashMap::new();
+        let mut states = vec![];
+        let mut state = start;
+        while !first_visits.contains_key(&state) {
+            first_visits.insert(state.clone(), states.len());
+            let next = step(&state);
+            states.push(state);
+            state = next;
+        }
+        let tail = first_visits[&state];
+        return Cycle { length: states.len() - tail, states, tail };
+    }
+}
+
+impl<S> Cycle<S> {
+    /// The state after `steps` steps.
+    pub fn state_at(&self, steps: u64) -> &S {
+        let (tail, length) = (self.tail as u64, self.length as u64);
+        if steps < tail {
+            return &self.states[steps as usize];
+        }
+        return &self.states[(tail + (steps - tail) % length) as usize];
+    }
+
+    /// The steps at which the walk is in a state matching `predicate`.
+    pub fn hits(&self, predicate: impl Fn(&S) -> bool) -> Hits {
+        let steps = (0..self.states.len() as u64).filter(|&step| predicate(&self.states[step as usize])).collect();
+        return Hits { steps, tail: self.tail as u64, length: self.length as u64 };
+    }
+}
+
+/// The steps at which a [`Cycle`] meets some condition: each of `steps`, all below
+/// `tail + length`, and those from `tail` on again every `length` steps.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hits {
+    pub steps: Vec<u64>,
+    pub tail: u64,
+    pub length: u64,
+}
+
+impl Hits {
+    pub fn contains(&self, step: u64) -> bool {
+        let step = if step < self.tail { step } else { self.tail + (step - self.tail) % self.length };
+        return self.steps.binary_search(&step).is_ok();
+    }
+
+    /// Whether the only hit is in the cycle, at the step equal to its length, so hits fall on the
+    /// multiples of that length.
+    fn is_multiples_of_length(&self) -> bool {
+        return self.steps == [self.length] && (1..=self.length).contains(&self.tail);
+    }
+}
+
+/// How [`first_common_hit`] found its answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// Every walk hits exactly on the multiples of its cycle length, so the answer is their lcm.
+    Lcm,
+    /// The walks all hit together before the last of them enters its cycle.
+    Tail,
+    /// The hits of every cycle combined with the Chinese remainder theorem.
+    Crt,
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Strategy::Lcm => f.pad("lcm of cycle lengths"),
+            Strategy::Tail => f.pad("before every cycle"),
+            Strategy::Crt => f.pad("chinese remainder theorem"),
+        }
+    }
+}
+
+/// The first step at which every walk hits at once, with the strategy that found it, or `None`
+/// if they never do.
+///
+/// Fails without walks and when the step does not fit in 64 bits. Walks with several hits per
+/// cycle multiply the congruences to combine.
+pub fn first_common_hit(walks: &[Hits]) -> Result<Option<(u64, Strategy)>> {
+    let Some(longest_tail) = walks.iter().max_by_key(|walk| walk.tail) else {
+        return Err(Error::Invalid("there are no walks to combine".to_string()));
+    };
+    let overflow = || Error::Invalid("the first common step does not fit in 64 bits".to_string());
+
+    if walks.iter().all(Hits::is_multiples_of_length) {
+        let step = walks.iter().try_fold(1, |step, walk| math::lcm(step, walk.length)).ok_or_else(overflow)?;
+        return Ok(Some((step, Strategy::Lcm)));
+    }
+
+    // Before the longest tail ends, only the hits in that tail are candidates.
+    let tail_hits = longest_tail.steps.iter().take_while(|&&step| step < longest_tail.tail);
+    for &step in tail_hits {
+        if walks.iter().all(|walk| walk.contains(step)) {
+            return Ok(Some((step, Strategy::Tail)));
+        }
+    }
+
+    // From then on, each walk hits exactly at the residues of its cycle hits.
+    let mut congruences = vec![Congruence::new(0, 1)];
+    for walk in walks {
+        let modulus = i128::from(walk.length);
+        let residues = walk.steps.iter().filter(|&&step| step >= walk.tail).map(|&step| Congruence::new(i128::from(step), modulus));
+        let mut combined = vec![];
+        for residue in residues {
+            for congruence in &congruences {
+                if let Some(congruence) = math::crt(&[*congruence, residue])? {
+                    combined.push(congruence);
+                }
+            }
+        }
+        combined.sort_by_key(|congruence| (congruence.modulus, congruence.residue));
+        combined.dedup();
+        congruences = combined;
+    }
+
+    // Steps past 64 bits are left out, so they only matter when no other step remains.
+    let start = i128::from(longest_tail.tail);
+    let steps = congruences
+        .iter()
+        .map(|&Congruence { residue, modulus }| {
+            if residue >= start {
+                return u64::try_from(residue).ok();
+            }
+            let cycles = (start - residue).checked_add(modulus - 1)? / modulus;
+            return u64::try_from(modulus.checked_mul(cycles)?.checked_add(residue)?).ok();
+        })
+        .collect::<Vec<Option<u64>>>();
+    return match steps.iter().flatten().min() {
+        Some(&step) => Ok(Some((step, Strategy::Crt))),
+        None if steps.is_empty() => Ok(None),
+        None => Err(overflow()),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::Strategy;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_find_cycle() {
+        let cycle = Cycle::find(0_u32, |&state| if state < 3 { state + 1 } else { 1 });
+        assert_eq!((cycle.states.as_slice(), cycle.tail, cycle.length), ([0, 1, 2, 3].as_slice(), 1, 3));
+        assert_eq!(*cycle.state_at(7), 1);
+        let hits = cycle.hits(|&state| state == 2);
+        assert_eq!(hits, Hits { steps: vec![2], tail: 1, length: 3 });
+        assert!(hits.contains(2) && hits.contains(8) && !hits.contains(9));
+    }
+
+    #[test]
+    fn test_strategies() {
+        let multiples = |length| Hits { steps: vec![length], tail: 1, length };
+        assert_eq!(first_common_hit(&[multiples(4), multiples(6)]).unwrap(), Some((12, Strategy::Lcm)));
+        let in_tail = Hits { steps: vec![1], tail: 2, length: 1 };
+        assert_eq!(first_common_hit(&[multiples(1), in_tail]).unwrap(), Some((1, Strategy::Tail)));
+
+        let early = Hits { steps: vec![1, 5], tail: 5, length: 2 };
+        let periodic = Hits { steps: vec![0, 1], tail: 0, length: 2 };
+        assert_eq!(first_common_hit(&[early, periodic]).unwrap(), Some((1, Strategy::Tail)));
+
+        let odd = Hits { steps: vec![1], tail: 0, length: 2 };
+        let shifted = Hits { steps: vec![2], tail: 0, length: 3 };
+        assert_eq!(first_common_hit(&[odd.clone(), shifted]).unwrap(), Some((5, Strategy::Crt)));
+        let even = Hits { steps: vec![0], tail: 0, length: 4 };
+        assert_eq!(first_common_hit(&[odd, even]).unwrap(), None);
+
+        assert!(first_common_hit(&[]).is_err());
+        let huge = |length| Hits { steps: vec![length], tail: 1, length };
+        assert!(first_common_hit(&[huge(u64::MAX), huge(u64::MAX - 1)]).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_first_common_hit_against_brute_force(
+            successors in prop::collection::vec(0_usize..8, 8),
+            targets in prop::collection::vec(any::<bool>(), 8),
+            starts in prop::collection::vec(0_usize..8, 1..4),
+        ) {
+            let cycles = starts.iter().map(|&start| Cycle::find(start, |&state| successors[state])).collect::<Vec<_>>();
+            let walks = cycles.iter().map(|cycle| cycle.hits(|&state| targets[state])).collect::<Vec<_>>();
+            // Every walk is periodic from the longest tail on, with the lcm of the lengths as period.
+            let period = walks.iter().try_fold(1, |period, walk| math::lcm(period, walk.length)).unwrap();
+            let limit = walks.iter().map(|walk| walk.tail).max().unwrap() + period;
+            let expected = (0..limit).find(|&step| cycles.iter().all(|cycle| targets[*cycle.state_at(step)]));
+            prop_assert_eq!(first_common_hit(&walks).unwrap().map(|(step, _)| step), expected);
+        }
+    }
+}
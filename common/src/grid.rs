@@ -0,0 +1,284 @@
+use std::collections::HashSet;
+
+use crate::parse::{self, ParseResult};
+
+/// A cell of a [`Grid`], as `(row, column)` from the top left.
+pub type Position = (usize, usize);
+
+const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const OFFSETS_8: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// A rectangular grid of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// A horizontal run of consecutive cells in one row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Run {
+    pub row: usize,
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Run {
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        return (self.start..self.start + self.length).map(|column| (self.row, column));
+    }
+
+    /// Whether `position` touches the run, diagonals included, without being part of it.
+    pub fn is_adjacent_to(&self, (row, column): Position) -> bool {
+        let touches = row.abs_diff(self.row) <= 1 && column + 1 >= self.start && column <= self.start + self.length;
+        return touches && !(row == self.row && (self.start..self.start + self.length).contains(&column));
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "a {}x{} grid needs {} cells", width, height, width * height);
+        return Grid { width, height, cells };
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Position) -> T) -> Self {
+        let cells = (0..height).flat_map(|row| (0..width).map(move |column| (row, column))).map(&mut cell).collect();
+        return Grid { width, height, cells };
+    }
+
+    /// Reads one cell per character, failing on characters `cell` rejects and on ragged lines.
+    /// Every character up to the line break is a cell, trailing spaces included.
+    pub fn parse_with(input: &str, expected: &str, cell: impl Fn(char) -> Option<T>) -> ParseResult<Self> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for mut scanner in parse::lines(input) {
+            let mut row_width = 0;
+            while width.is_none_or(|width| row_width < width) && !scanner.rest().is_empty() {
+                let character = scanner.character(expected, |c| cell(c).is_some())?;
+                cells.push(cell(character).unwrap());
+                row_width += 1;
+            }
+            if width.is_some_and(|width| row_width < width) {
+                return Err(scanner.error(expected));
+            }
+            if !scanner.rest().is_empty() {
+                return Err(scanner.error("end of line"));
+            }
+            width = Some(row_width);
+            height += 1;
+        }
+        return Ok(Grid { width: width.unwrap_or(0), height, cells });
+    }
+
+    pub fn width(&self) -> usize {
+        return self.width;
+    }
+
+    pub fn height(&self) -> usize {
+        return self.height;
+    }
+
+    pub fn contains(&self, (row, column): Position) -> bool {
+        return row < self.height && column < self.width;
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        if !self.contains(position) {
+            return None;
+        }
+        return Some(&self.cells[position.0 * self.width + position.1]);
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        if !self.contains(position) {
+            return None;
+        }
+        return Some(&mut self.cells[position.0 * self.width + position.1]);
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        return &self.cells[row * self.width..(row + 1) * self.width];
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        return (0..self.height).map(|row| self.row(row));
+    }
+
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        assert!(column < self.width, "column {} is outside a grid {} wide", column, self.width);
+        return (0..self.height).map(move |row| &self.cells[row * self.width + column]);
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        return (0..self.width).map(|column| self.column(column));
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        return (0..self.height).flat_map(move |row| (0..width).map(move |column| (row, column)));
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        return self.positions().zip(self.cells.iter());
+    }
+
+    /// The orthogonal neighbors of `position` inside the grid.
+    pub fn neighbors4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        return self.offset_positions(position, &OFFSETS_4);
+    }
+
+    /// The orthogonal and diagonal neighbors of `position` inside the grid.
+    pub fn neighbors8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        return self.offset_positions(position, &OFFSETS_8);
+    }
+
+    fn offset_positions<'a>(
+        &'a self,
+        (row, column): Position,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = Position> + 'a {
+        return offsets.iter().filter_map(move |&(row_offset, column_offset)| {
+            let position = (row.checked_add_signed(row_offset)?, column.checked_add_signed(column_offset)?);
+            return self.contains(position).then_some(position);
+        });
+    }
+
+    pub fn find_all(&self, predicate: impl Fn(&T) -> bool) -> Vec<Position> {
+        return self.iter().filter(|(_, cell)| predicate(cell)).map(|(position, _)| position).collect();
+    }
+
+    /// The maximal horizontal runs of cells matching `predicate`, row by row.
+    pub fn runs(&self, predicate: impl Fn(&T) -> bool) -> Vec<Run> {
+        let mut runs = vec![];
+        for (row, cells) in self.rows().enumerate() {
+            let mut start = None;
+            for column in 0..=self.width {
+                match (start, cells.get(column).is_some_and(&predicate)) {
+                    (None, true) => start = Some(column),
+                    (Some(run_start), false) => {
+                        runs.push(Run { row, start: run_start, length: column - run_start });
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        return runs;
+    }
+
+    /// The cells matching `predicate` connected to `start` through orthogonal neighbors.
+    pub fn region(&self, start: Position, predicate: impl Fn(&T) -> bool) -> Vec<Position> {
+        if !self.get(start).is_some_and(&predicate) {
+            return vec![];
+        }
+        let mut seen = HashSet::from([start]);
+        let mut region = vec![start];
+        let mut index = 0;
+        while index < region.len() {
+            for neighbor in self.neighbors4(region[index]) {
+                if predicate(&self[neighbor]) && seen.insert(neighbor) {
+                    region.push(neighbor);
+                }
+            }
+            index += 1;
+        }
+        return region;
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        return Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() };
+    }
+}
+
+impl Grid<char> {
+    pub fn parse(input: &str) -> ParseResult<Self> {
+        return Grid::parse_with(input, "a cell", Some);
+    }
+}
+
+impl<T> std::ops::Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        return self.get(position).unwrap_or_else(|| {
+            panic!("{:?} is outside a {}x{} grid", position, self.width, self.height)
+        });
+    }
+}
+
+impl<T> std::ops::IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        return self.get_mut(position).unwrap_or_else(|| {
+            panic!("{:?} is outside a {}x{} grid", position, width, height)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse("ab\ncd\nef").unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get((0, 2)), None);
+        assert_eq!(grid.column(1).collect::<String>(), "bdf");
+        assert_eq!(grid.rows().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>(), ["ab", "cd", "ef"]);
+
+        let error = Grid::parse("abc\nab\nabc").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 3, "a cell"));
+        let error = Grid::parse("abc\nabcd").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 4, "`d`"));
+
+        // Spaces are cells like any other character, in the last column too.
+        let grid = Grid::parse("a \nbc\r\n  ").unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.column(1).collect::<String>(), " c ");
+        assert_eq!(Grid::parse(" a\n  ").unwrap().row(1), [' ', ' ']);
+        let error = Grid::parse("ab\ncd  ").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 3, "end of line"));
+        let error = Grid::parse("ab  \ncd").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 3, "a cell"));
+
+        let error = Grid::parse_with("1.\n2x", "a digit or `.`", |c| (c == '.' || c.is_ascii_digit()).then_some(c)).unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 2, "`x`"));
+    }
+
+    #[test]
+    #[should_panic(expected = "column 2 is outside a grid 2 wide")]
+    fn test_column_out_of_bounds() {
+        let grid = Grid::parse("ab\ncd").unwrap();
+        let _ = grid.column(2);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::from_fn(3, 2, |(row, column)| row * 3 + column);
+        assert_eq!(grid.neighbors4((0, 0)).collect::<Vec<_>>(), [(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors8((1, 1)).collect::<Vec<_>>(), [(0, 0), (0, 1), (0, 2), (1, 0), (1, 2)]);
+        assert_eq!(grid.find_all(|&cell| cell % 2 == 1), [(0, 1), (1, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn test_runs_and_regions() {
+        let grid = Grid::parse("467..114\n...*....\n..35..63").unwrap();
+        let runs = grid.runs(char::is_ascii_digit);
+        assert_eq!(runs[1], Run { row: 0, start: 5, length: 3 });
+        assert_eq!(runs[3], Run { row: 2, start: 6, length: 2 });
+        assert_eq!(runs[2].positions().collect::<Vec<_>>(), [(2, 2), (2, 3)]);
+        assert!(runs[0].is_adjacent_to((1, 3)));
+        assert!(!runs[1].is_adjacent_to((1, 3)));
+        assert!(!runs[0].is_adjacent_to((0, 1)));
+
+        let mut region = grid.region((0, 3), |&c| c == '.');
+        region.sort();
+        assert_eq!(region, [(0, 3), (0, 4), (1, 4), (1, 5), (1, 6), (1, 7), (2, 4), (2, 5)]);
+        assert!(grid.region((0, 0), |&c| c == '.').is_empty());
+    }
+}
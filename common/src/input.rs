@@ -0,0 +1,154 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{Error, Result};
+
+/// File names accepted for each canonical input name, in order of preference.
+const ALIASES: [(&str, &[&str]); 4] = [
+    ("input", &["input"]),
+    ("example", &["example", "test", "test-part1", "test-part1-1"]),
+    ("example-2", &["example-2", "test-part1-2"]),
+    ("example-part2", &["example-part2", "test-part2"]),
+];
+
+/// The directory holding the `year-YYYY/day-DD` input folders and the `answers` directory.
+///
+/// Defaults to the workspace root, and can be moved elsewhere with `AOC_DATA_DIR`.
+pub fn data_root() -> PathBuf {
+    match env::var_os("AOC_DATA_DIR") {
+        Some(root) => PathBuf::from(root),
+        None => Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf(),
+    }
+}
+
+/// Strips the trailing line breaks that editors leave at the end of input files.
+pub fn trim_input(contents: &str) -> &str {
+    return contents.trim_end_matches(['\r', '\n']);
+}
+
+/// The named inputs of one day, such as `input`, `example` or `example-part2`.
+#[derive(Debug, Clone)]
+pub struct Inputs {
+    year: u16,
+    day: u8,
+    directory: PathBuf,
+}
+
+impl Inputs {
+    pub fn for_day(year: u16, day: u8) -> Inputs {
+        return Inputs::new(&data_root(), year, day);
+    }
+
+    pub fn new(root: &Path, year: u16, day: u8) -> Inputs {
+        return Inputs {
+            year,
+            day,
+            directory: root.join(format!("year-{}", year)).join(format!("day-{:02}", day)),
+        };
+    }
+
+    pub fn directory(&self) -> &Path {
+        return &self.directory;
+    }
+
+    pub fn path(&self, name: &str) -> Result<PathBuf> {
+        let candidates = ALIASES
+            .iter()
+            .find(|(canonical, _)| *canonical == name)
+            .map_or(vec![name], |(_, file_stems)| file_stems.to_vec());
+
+        return candidates
+            .iter()
+            .map(|file_stem| self.directory.join(format!("{}.txt", file_stem)))
+            .find(|path| path.is_file())
+            .ok_or_else(|| {
+                Error::Invalid(format!(
+                    "no `{}` input for {} day {:02} in {}",
+                    name,
+                    self.year,
+                    self.day,
+                    self.directory.display()
+                ))
+            });
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        return self.path(name).is_ok();
+    }
+
+    pub fn read(&self, name: &str) -> Result<String> {
+        let contents = fs::read_to_string(self.path(name)?)?;
+        return Ok(trim_input(&contents).to_string());
+    }
+
+    /// The canonical names of every input file present, sorted.
+    pub fn names(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("txt") {
+                continue;
+            }
+            let Some(file_stem) = path.file_stem().and_then(|file_stem| file_stem.to_str()) else {
+                continue;
+            };
+            let name = ALIASES
+                .iter()
+                .find(|(_, file_stems)| file_stems.contains(&file_stem))
+                .map_or(file_stem, |(canonical, _)| canonical);
+            if self.path(name).ok().as_deref() == Some(path.as_path()) {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        return Ok(names);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Inputs of 2023 day 08 with `files` in a directory removed when the `TempDir` drops.
+    fn temporary_inputs(files: &[&str]) -> (TempDir, Inputs) {
+        let root = TempDir::new().unwrap();
+        let inputs = Inputs::new(root.path(), 2023, 8);
+        fs::create_dir_all(inputs.directory()).unwrap();
+        for file in files {
+            fs::write(inputs.directory().join(file), "RL\n").unwrap();
+        }
+        return (root, inputs);
+    }
+
+    #[test]
+    fn test_legacy_names() {
+        let (_root, inputs) = temporary_inputs(&["input.txt", "test-part1-1.txt", "test-part1-2.txt", "test-part2.txt"]);
+        assert!(inputs.path("example").unwrap().ends_with("day-08/test-part1-1.txt"));
+        assert!(inputs.path("example-2").unwrap().ends_with("day-08/test-part1-2.txt"));
+        assert!(inputs.path("example-part2").unwrap().ends_with("day-08/test-part2.txt"));
+        assert_eq!(inputs.read("input").unwrap(), "RL");
+        assert_eq!(inputs.names().unwrap(), ["example", "example-2", "example-part2", "input"]);
+    }
+
+    #[test]
+    fn test_preferred_names() {
+        let (_root, inputs) = temporary_inputs(&["example.txt", "test.txt", "large.txt"]);
+        assert!(inputs.path("example").unwrap().ends_with("day-08/example.txt"));
+        assert!(inputs.path("large").unwrap().ends_with("day-08/large.txt"));
+        assert_eq!(inputs.names().unwrap(), ["example", "large"]);
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let (_root, inputs) = temporary_inputs(&["test.txt"]);
+        assert!(!inputs.exists("input"));
+        assert!(inputs.read("input").is_err());
+    }
+
+    #[test]
+    fn test_trim_input() {
+        assert_eq!(trim_input("1\n2\r\n\n"), "1\n2");
+    }
+}
@@ -0,0 +1,301 @@
+/// The half-open interval `start..end`, empty when `end <= start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Copy + Ord> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        return Interval { start, end };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.end <= self.start;
+    }
+
+    pub fn contains(&self, point: T) -> bool {
+        return self.start <= point && point < self.end;
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Interval<T> {
+        return Interval::new(self.start.max(other.start), self.end.min(other.end));
+    }
+}
+
+impl Interval<i64> {
+    /// The interval of `length` points from `start`, or `None` if its end overflows.
+    pub fn from_length(start: i64, length: i64) -> Option<Self> {
+        return Some(Interval::new(start, start.checked_add(length)?));
+    }
+
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        return self.end.abs_diff(self.start);
+    }
+
+    /// The interval moved by `offset`, or `None` if either bound overflows.
+    pub fn shifted(&self, offset: i64) -> Option<Self> {
+        return Some(Interval::new(self.start.checked_add(offset)?, self.end.checked_add(offset)?));
+    }
+}
+
+/// A set of points stored as sorted, disjoint and non-adjacent half-open intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Copy + Ord> Default for IntervalSet<T> {
+    fn default() -> Self {
+        return IntervalSet { intervals: vec![] };
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> Self {
+        return IntervalSet::normalized(intervals.into_iter().collect());
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        return IntervalSet::default();
+    }
+
+    /// Sorts `intervals`, drops empty ones and merges the ones that overlap or touch.
+    fn normalized(mut intervals: Vec<Interval<T>>) -> Self {
+        intervals.retain(|interval| !interval.is_empty());
+        intervals.sort_by_key(|interval| interval.start);
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        return IntervalSet { intervals: merged };
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        return &self.intervals;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.intervals.is_empty();
+    }
+
+    pub fn min(&self) -> Option<T> {
+        return self.intervals.first().map(|interval| interval.start);
+    }
+
+    pub fn contains(&self, point: T) -> bool {
+        let index = self.intervals.partition_point(|interval| interval.end <= point);
+        return self.intervals.get(index).is_some_and(|interval| interval.contains(point));
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = IntervalSet::normalized(intervals);
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        return self.intervals.iter().chain(&other.intervals).copied().collect();
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = vec![];
+        let (mut left, mut right) = (0, 0);
+        while left < self.intervals.len() && right < other.intervals.len() {
+            let (first, second) = (self.intervals[left], other.intervals[right]);
+            let overlap = first.intersection(&second);
+            if !overlap.is_empty() {
+                intervals.push(overlap);
+            }
+            if first.end < second.end {
+                left += 1;
+            } else {
+                right += 1;
+            }
+        }
+        return IntervalSet { intervals };
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = vec![];
+        let mut removed = other.intervals.iter().peekable();
+        for &interval in &self.intervals {
+            let mut start = interval.start;
+            while let Some(hole) = removed.peek() {
+                if hole.end <= start {
+                    removed.next();
+                    continue;
+                }
+                if hole.start >= interval.end {
+                    break;
+                }
+                if hole.start > start {
+                    intervals.push(Interval::new(start, hole.start));
+                }
+                start = hole.end;
+                if hole.end > interval.end {
+                    break;
+                }
+                removed.next();
+            }
+            if start < interval.end {
+                intervals.push(Interval::new(start, interval.end));
+            }
+        }
+        return IntervalSet { intervals };
+    }
+}
+
+impl IntervalSet<i64> {
+    /// The number of points in the set.
+    pub fn count(&self) -> u128 {
+        return self.intervals.iter().map(|interval| u128::from(interval.len())).sum();
+    }
+
+    /// Every point moved by `offset`, or `None` if any bound overflows.
+    pub fn shifted(&self, offset: i64) -> Option<IntervalSet<i64>> {
+        let intervals = self.intervals.iter().map(|interval| interval.shifted(offset)).collect::<Option<_>>()?;
+        return Some(IntervalSet { intervals });
+    }
+}
+
+/// Moves the points of source intervals by an offset each, leaving other points in place.
+///
+/// When source intervals overlap, the rule added first applies.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PiecewiseOffsetMap {
+    rules: Vec<(Interval<i64>, i64)>,
+}
+
+impl PiecewiseOffsetMap {
+    pub fn new() -> Self {
+        return PiecewiseOffsetMap::default();
+    }
+
+    pub fn add_rule(&mut self, source: Interval<i64>, offset: i64) {
+        self.rules.push((source, offset));
+    }
+
+    pub fn rules(&self) -> &[(Interval<i64>, i64)] {
+        return &self.rules;
+    }
+
+    /// Where `point` is mapped to, or `None` if that overflows.
+    pub fn map_point(&self, point: i64) -> Option<i64> {
+        return match self.rules.iter().find(|(source, _)| source.contains(point)) {
+            Some(&(_, offset)) => point.checked_add(offset),
+            None => Some(point),
+        };
+    }
+
+    /// The image of every point of `set`, or `None` if any of them overflows.
+    pub fn map(&self, set: &IntervalSet<i64>) -> Option<IntervalSet<i64>> {
+        let mut unmapped = set.clone();
+        let mut mapped = IntervalSet::new();
+        for &(source, offset) in &self.rules {
+            let source = IntervalSet::from_iter([source]);
+            mapped = mapped.union(&unmapped.intersection(&source).shifted(offset)?);
+            unmapped = unmapped.difference(&source);
+        }
+        return Some(mapped.union(&unmapped));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    const LOW: i64 = -40;
+    const HIGH: i64 = 40;
+
+    fn points(set: &IntervalSet<i64>) -> BTreeSet<i64> {
+        return set.intervals().iter().flat_map(|interval| interval.start..interval.end).collect();
+    }
+
+    fn intervals() -> impl Strategy<Value = Vec<Interval<i64>>> {
+        return prop::collection::vec((LOW..HIGH, 0_i64..12), 0..6)
+            .prop_map(|pairs| pairs.into_iter().map(|(start, length)| Interval::new(start, start + length)).collect());
+    }
+
+    fn interval_set() -> impl Strategy<Value = IntervalSet<i64>> {
+        return intervals().prop_map(IntervalSet::from_iter);
+    }
+
+    fn is_normalized(set: &IntervalSet<i64>) -> bool {
+        let intervals = set.intervals();
+        return intervals.iter().all(|interval| !interval.is_empty())
+            && intervals.windows(2).all(|pair| pair[0].end < pair[1].start);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let set = IntervalSet::from_iter([Interval::new(5, 8), Interval::new(0, 2), Interval::new(2, 3), Interval::new(7, 7)]);
+        assert_eq!(set.intervals(), [Interval::new(0, 3), Interval::new(5, 8)]);
+        assert!(set.contains(2) && !set.contains(3) && !set.contains(8));
+        assert_eq!((set.min(), set.count()), (Some(0), 6));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Interval::from_length(i64::MAX - 1, 2), None);
+        let set = IntervalSet::from_iter([Interval::new(i64::MAX - 5, i64::MAX)]);
+        assert_eq!(set.shifted(6), None);
+        assert_eq!(set.count(), 5);
+
+        let mut map = PiecewiseOffsetMap::new();
+        map.add_rule(Interval::new(0, 10), i64::MAX);
+        assert_eq!(map.map_point(1), None);
+        assert_eq!(map.map_point(10), Some(10));
+        assert_eq!(map.map(&IntervalSet::from_iter([Interval::new(5, 6)])), None);
+    }
+
+    proptest! {
+        #[test]
+        fn test_set_algebra(first in interval_set(), second in interval_set()) {
+            let (first_points, second_points) = (points(&first), points(&second));
+            let union = first.union(&second);
+            let intersection = first.intersection(&second);
+            let difference = first.difference(&second);
+
+            prop_assert_eq!(points(&union), &first_points | &second_points);
+            prop_assert_eq!(points(&intersection), &first_points & &second_points);
+            prop_assert_eq!(points(&difference), &first_points - &second_points);
+            prop_assert!(is_normalized(&union) && is_normalized(&intersection) && is_normalized(&difference));
+            for point in LOW - 1..HIGH + 12 {
+                prop_assert_eq!(first.contains(point), first_points.contains(&point));
+            }
+        }
+
+        #[test]
+        fn test_insert(intervals in intervals()) {
+            let mut set = IntervalSet::new();
+            for &interval in &intervals {
+                set.insert(interval);
+            }
+            prop_assert!(is_normalized(&set));
+            prop_assert_eq!(set, IntervalSet::from_iter(intervals));
+        }
+
+        #[test]
+        fn test_map(set in interval_set(), rules in prop::collection::vec((LOW..HIGH, 0_i64..12, -30_i64..30), 0..5)) {
+            let mut map = PiecewiseOffsetMap::new();
+            for (start, length, offset) in rules {
+                map.add_rule(Interval::new(start, start + length), offset);
+            }
+            let mapped = map.map(&set).unwrap();
+            let expected = points(&set).into_iter().map(|point| map.map_point(point).unwrap()).collect::<BTreeSet<_>>();
+            prop_assert!(is_normalized(&mapped));
+            prop_assert_eq!(points(&mapped), expected);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+pub mod answers;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod math;
+pub mod parse;
+pub mod sequence;
+
+mod answer;
+mod error;
+mod puzzle;
+mod solution;
+
+pub use answer::Answer;
+pub use error::{Error, Result};
+pub use input::Inputs;
+pub use parse::ParseError;
+pub use puzzle::{Parsed, Part, Puzzle};
+pub use solution::Solution;
@@ -0,0 +1,236 @@
+use std::ops::Div;
+
+use crate::{Error, Result};
+
+/// The primitive integer types the number-theory helpers work with.
+pub trait Integer: Copy + Ord + Div<Output = Self> {
+    const ZERO: Self;
+
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    /// The remainder, or `None` when dividing by zero or when it overflows, as for `i64::MIN % -1`.
+    fn checked_rem(self, other: Self) -> Option<Self>;
+    /// The absolute value, or `None` when it does not fit, as for `i64::MIN`.
+    fn checked_abs(self) -> Option<Self>;
+}
+
+macro_rules! impl_signed_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl Integer for $integer {
+                const ZERO: Self = 0;
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    return <$integer>::checked_mul(self, other);
+                }
+
+                fn checked_rem(self, other: Self) -> Option<Self> {
+                    return <$integer>::checked_rem(self, other);
+                }
+
+                fn checked_abs(self) -> Option<Self> {
+                    return <$integer>::checked_abs(self);
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_unsigned_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl Integer for $integer {
+                const ZERO: Self = 0;
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    return <$integer>::checked_mul(self, other);
+                }
+
+                fn checked_rem(self, other: Self) -> Option<Self> {
+                    return <$integer>::checked_rem(self, other);
+                }
+
+                fn checked_abs(self) -> Option<Self> {
+                    return Some(self);
+                }
+            }
+        )*
+    };
+}
+
+impl_signed_integer!(i8, i16, i32, i64, i128, isize);
+impl_unsigned_integer!(u8, u16, u32, u64, u128, usize);
+
+/// The non-negative greatest common divisor, with `gcd(0, 0) = 0`. `None` if it does not fit in `T`.
+pub fn gcd<T: Integer>(first: T, second: T) -> Option<T> {
+    let (mut first, mut second) = (first, second);
+    while second != T::ZERO {
+        // Only `MIN % -1` overflows, and every number is a multiple of -1.
+        (first, second) = (second, first.checked_rem(second).unwrap_or(T::ZERO));
+    }
+    return first.checked_abs();
+}
+
+/// The non-negative least common multiple, with `lcm(0, n) = 0`. `None` if it does not fit in `T`.
+pub fn lcm<T: Integer>(first: T, second: T) -> Option<T> {
+    if first == T::ZERO || second == T::ZERO {
+        return Some(T::ZERO);
+    }
+    let divisor = gcd(first, second)?;
+    return (first / divisor).checked_mul(second)?.checked_abs();
+}
+
+/// `(g, x, y)` such that `a * x + b * y = g`, where `g` is the non-negative gcd of `a` and `b`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_remainder, mut remainder) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while remainder != 0 {
+        let quotient = old_remainder / remainder;
+        (old_remainder, remainder) = (remainder, old_remainder - quotient * remainder);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_remainder < 0 {
+        return (-old_remainder, -old_x, -old_y);
+    }
+    return (old_remainder, old_x, old_y);
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`, if `a` and `modulus` are coprime.
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    if modulus <= 0 {
+        return None;
+    }
+    let (divisor, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if divisor != 1 {
+        return None;
+    }
+    return Some(x.rem_euclid(modulus));
+}
+
+/// The integers `x` with `x ≡ residue (mod modulus)`, for a positive modulus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Congruence {
+    pub residue: i128,
+    pub modulus: i128,
+}
+
+impl Congruence {
+    /// The congruence with its residue reduced into `0..modulus`.
+    pub fn new(residue: i128, modulus: i128) -> Self {
+        assert!(modulus > 0, "a congruence needs a positive modulus, found {}", modulus);
+        return Congruence { residue: residue.rem_euclid(modulus), modulus };
+    }
+}
+
+/// Solves a system of congruences whose moduli need not be coprime, with the Chinese remainder
+/// theorem.
+///
+/// Returns the combined congruence, `Ok(None)` when the system has no solution, and an error when
+/// the combined modulus overflows.
+pub fn crt(congruences: &[Congruence]) -> Result<Option<Congruence>> {
+    let mut combined = Congruence::new(0, 1);
+    for congruence in congruences {
+        let congruence = Congruence::new(congruence.residue, congruence.modulus);
+        let (divisor, x, _) = extended_gcd(combined.modulus, congruence.modulus);
+        let difference = congruence.residue - combined.residue;
+        if difference % divisor != 0 {
+            return Ok(None);
+        }
+        let overflow = || Error::Invalid("the combined modulus of the congruences overflows".to_string());
+        let modulus = (combined.modulus / divisor).checked_mul(congruence.modulus).ok_or_else(overflow)?;
+        let step_modulus = congruence.modulus / divisor;
+        let steps = mul_mod((difference / divisor).rem_euclid(step_modulus), x.rem_euclid(step_modulus), step_modulus);
+        let residue = combined.residue.checked_add(combined.modulus.checked_mul(steps).ok_or_else(overflow)?).ok_or_else(overflow)?;
+        combined = Congruence::new(residue, modulus);
+    }
+    return Ok(Some(combined));
+}
+
+/// `a * b mod modulus` for `a` and `b` in `0..modulus`, without overflowing.
+fn mul_mod(a: i128, b: i128, modulus: i128) -> i128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+    let (mut a, mut b, mut product) = (a, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            product = add_mod(product, a, modulus);
+        }
+        a = add_mod(a, a, modulus);
+        b >>= 1;
+    }
+    return product;
+}
+
+/// `a + b mod modulus` for `a` and `b` in `0..modulus`, without overflowing.
+fn add_mod(a: i128, b: i128, modulus: i128) -> i128 {
+    if a >= modulus - b {
+        return a - (modulus - b);
+    }
+    return a + b;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), Some(6));
+        assert_eq!(gcd(-12_i32, 18), Some(6));
+        assert_eq!(gcd(0_u8, 7), Some(7));
+        assert_eq!(gcd(0_i64, 0), Some(0));
+        assert_eq!(gcd(i64::MIN, 0), None);
+        assert_eq!(gcd(-1_i64, i64::MIN), Some(1));
+        assert_eq!(gcd(i64::MIN, -1), Some(1));
+        assert_eq!(gcd(i64::MIN, i64::MIN), None);
+        assert_eq!(lcm(4_u64, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(-4, 6), Some(12));
+        assert_eq!(lcm(i64::MAX, i64::MAX - 1), None);
+        assert_eq!(lcm(i64::MIN, -1), None);
+        assert_eq!(lcm(-1, i64::MIN), None);
+        assert_eq!(lcm(i64::MIN, 2), None);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(3, 0), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        let solution = crt(&[Congruence::new(2, 3), Congruence::new(3, 5), Congruence::new(2, 7)]).unwrap();
+        assert_eq!(solution, Some(Congruence::new(23, 105)));
+        assert_eq!(crt(&[Congruence::new(3, 4), Congruence::new(1, 6)]).unwrap(), Some(Congruence::new(7, 12)));
+        assert_eq!(crt(&[Congruence::new(0, 4), Congruence::new(1, 6)]).unwrap(), None);
+        assert!(crt(&[Congruence::new(0, i128::MAX), Congruence::new(0, i128::MAX - 1)]).is_err());
+
+        let large = i128::MAX / 3;
+        assert_eq!(mul_mod(large - 1, large - 2, large), 2);
+    }
+
+    proptest! {
+        #[test]
+        fn test_extended_gcd(a in -1000_i128..1000, b in -1000_i128..1000) {
+            let (divisor, x, y) = extended_gcd(a, b);
+            prop_assert_eq!(Some(divisor), gcd(a, b));
+            prop_assert_eq!(a * x + b * y, divisor);
+        }
+
+        #[test]
+        fn test_crt_against_brute_force(congruences in prop::collection::vec((0_i128..30, 1_i128..12), 1..4)) {
+            let congruences = congruences.into_iter().map(|(residue, modulus)| Congruence::new(residue, modulus)).collect::<Vec<_>>();
+            let modulus = congruences.iter().try_fold(1, |modulus, congruence| lcm(modulus, congruence.modulus)).unwrap();
+            let expected = (0..modulus)
+                .find(|x| congruences.iter().all(|congruence| x.rem_euclid(congruence.modulus) == congruence.residue))
+                .map(|residue| Congruence::new(residue, modulus));
+            prop_assert_eq!(crt(&congruences).unwrap(), expected);
+        }
+    }
+}
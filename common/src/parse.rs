@@ -0,0 +1,285 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Where and why an input could not be parsed. Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    /// Renders the error with the offending line of `input` and a caret under the column.
+    pub fn render(&self, input: &str) -> String {
+        let mut rendered = format!("{}\n", self);
+        let Some(line) = input.lines().nth(self.line - 1) else {
+            return rendered;
+        };
+
+        let gutter = self.line.to_string().len();
+        let width = match self.found.as_str() {
+            END_OF_LINE | END_OF_INPUT => 1,
+            found => found.trim_matches('`').chars().count().max(1),
+        };
+        rendered += &format!("{:gutter$} |\n", "");
+        rendered += &format!("{} | {}\n", self.line, line);
+        rendered += &format!("{:gutter$} | {:padding$}{}\n", "", "", "^".repeat(width), padding = self.column - 1);
+        return rendered;
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: expected {}, found {}", self.line, self.column, self.expected, self.found)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+const END_OF_LINE: &str = "end of line";
+const END_OF_INPUT: &str = "end of input";
+
+/// The error for input that stops before `expected` could be read.
+pub fn end_of_input(input: &str, expected: &str) -> ParseError {
+    return ParseError {
+        line: input.lines().count() + 1,
+        column: 1,
+        expected: expected.to_string(),
+        found: END_OF_INPUT.to_string(),
+    };
+}
+
+/// Numbered lines of `input`, each wrapped in a [`LineScanner`].
+pub fn lines(input: &str) -> impl Iterator<Item = LineScanner<'_>> {
+    return input.lines().enumerate().map(|(index, line)| LineScanner::new(index + 1, line));
+}
+
+/// Reads the tokens of one line left to right, keeping track of the column for errors.
+#[derive(Debug, Clone)]
+pub struct LineScanner<'a> {
+    line_number: usize,
+    line: &'a str,
+    position: usize,
+}
+
+impl<'a> LineScanner<'a> {
+    pub fn new(line_number: usize, line: &'a str) -> Self {
+        return LineScanner { line_number, line, position: 0 };
+    }
+
+    pub fn line_number(&self) -> usize {
+        return self.line_number;
+    }
+
+    pub fn line(&self) -> &'a str {
+        return self.line;
+    }
+
+    /// Byte offset of the next unread character.
+    pub fn position(&self) -> usize {
+        return self.position;
+    }
+
+    pub fn rest(&self) -> &'a str {
+        return &self.line[self.position..];
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        return self.rest().trim_start().is_empty();
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        self.position = self.line.len() - self.rest().trim_start().len();
+    }
+
+    pub fn skip_while(&mut self, predicate: impl Fn(char) -> bool) {
+        self.position = self.line.len() - self.rest().trim_start_matches(predicate).len();
+    }
+
+    /// Reads the next character, which must satisfy `predicate`, without skipping whitespace.
+    pub fn character(&mut self, expected: &str, predicate: impl Fn(char) -> bool) -> ParseResult<char> {
+        match self.rest().chars().next() {
+            Some(c) if predicate(c) => {
+                self.position += c.len_utf8();
+                return Ok(c);
+            }
+            Some(c) => return Err(self.error_spanning(c.len_utf8(), expected)),
+            None => return Err(self.error(expected)),
+        }
+    }
+
+    /// Reads `expected` exactly, after any whitespace.
+    pub fn literal(&mut self, expected: &str) -> ParseResult<()> {
+        self.skip_whitespace();
+        if !self.rest().starts_with(expected) {
+            return Err(self.error(&format!("`{}`", expected)));
+        }
+        self.position += expected.len();
+        return Ok(());
+    }
+
+    /// Reads an optionally negative integer, after any whitespace.
+    pub fn number<T: FromStr>(&mut self) -> ParseResult<T> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with('-'));
+        let digits = rest[sign..].bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 || rest[sign + digits..].starts_with(char::is_alphanumeric) {
+            let token = sign + rest[sign..].find(|c: char| !c.is_alphanumeric()).unwrap_or(rest.len() - sign);
+            if token == 0 {
+                return Err(self.error("a number"));
+            }
+            return Err(self.error_spanning(token, "a number"));
+        }
+        let number = rest[..sign + digits]
+            .parse::<T>()
+            .map_err(|_| self.error_spanning(sign + digits, "a number in range"))?;
+        self.position += sign + digits;
+        return Ok(number);
+    }
+
+    /// Reads whitespace-separated numbers up to the end of the line or to `terminator`.
+    pub fn numbers<T: FromStr>(&mut self, terminator: Option<char>) -> ParseResult<Vec<T>> {
+        let mut numbers = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.rest().is_empty() || terminator.is_some_and(|c| self.rest().starts_with(c)) {
+                return Ok(numbers);
+            }
+            numbers.push(self.number()?);
+        }
+    }
+
+    /// Reads a run of alphanumeric characters, after any whitespace.
+    pub fn word(&mut self) -> ParseResult<&'a str> {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let length = rest.find(|c: char| !c.is_alphanumeric()).unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error("a word"));
+        }
+        self.position += length;
+        return Ok(&rest[..length]);
+    }
+
+    /// Reads a word that must be one of `words`, after any whitespace.
+    pub fn one_of<'w>(&mut self, words: &[&'w str]) -> ParseResult<&'w str> {
+        self.skip_whitespace();
+        let mut lookahead = self.clone();
+        let word = lookahead.word().ok();
+        let Some(&word) = words.iter().find(|&&candidate| Some(candidate) == word) else {
+            let quoted = words.iter().map(|word| format!("`{}`", word)).collect::<Vec<_>>();
+            let expected = match quoted.split_last() {
+                Some((last, [])) => last.clone(),
+                Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+                None => "nothing".to_string(),
+            };
+            return Err(self.error(&expected));
+        };
+        *self = lookahead;
+        return Ok(word);
+    }
+
+    /// Fails unless only whitespace is left on the line.
+    pub fn end(&mut self) -> ParseResult<()> {
+        self.skip_whitespace();
+        if !self.rest().is_empty() {
+            return Err(self.error(END_OF_LINE));
+        }
+        return Ok(());
+    }
+
+    /// An error at the next unread token.
+    pub fn error(&self, expected: &str) -> ParseError {
+        let rest = self.rest();
+        let length = match rest.chars().next() {
+            None => 0,
+            Some(c) if c.is_alphanumeric() => rest.find(|c: char| !c.is_alphanumeric()).unwrap_or(rest.len()),
+            Some(c) => c.len_utf8(),
+        };
+        return self.error_spanning(length, expected);
+    }
+
+    /// An error covering the next `length` bytes.
+    pub fn error_spanning(&self, length: usize, expected: &str) -> ParseError {
+        let found = &self.rest()[..length];
+        return ParseError {
+            line: self.line_number,
+            column: self.line[..self.position].chars().count() + 1,
+            expected: expected.to_string(),
+            found: if found.is_empty() { END_OF_LINE.to_string() } else { format!("`{}`", found) },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_line() {
+        let mut scanner = LineScanner::new(1, "Card  12: 41 48 | 83 -6");
+        scanner.literal("Card").unwrap();
+        assert_eq!(scanner.number::<u32>().unwrap(), 12);
+        scanner.literal(":").unwrap();
+        assert_eq!(scanner.numbers::<i32>(Some('|')).unwrap(), [41, 48]);
+        scanner.literal("|").unwrap();
+        assert_eq!(scanner.numbers::<i32>(None).unwrap(), [83, -6]);
+        assert!(scanner.end().is_ok());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut scanner = LineScanner::new(3, "Game 7; 3 blue");
+        scanner.literal("Game").unwrap();
+        scanner.number::<u32>().unwrap();
+        assert_eq!(
+            scanner.literal(":").unwrap_err(),
+            ParseError { line: 3, column: 7, expected: "`:`".to_string(), found: "`;`".to_string() }
+        );
+
+        let mut scanner = LineScanner::new(1, "seeds: 79 x14");
+        scanner.literal("seeds:").unwrap();
+        let error = scanner.numbers::<i64>(None).unwrap_err();
+        assert_eq!((error.column, error.found.as_str()), (11, "`x14`"));
+
+        let mut scanner = LineScanner::new(1, "300, red");
+        assert_eq!(scanner.number::<u8>().unwrap_err().expected, "a number in range");
+        scanner.number::<u32>().unwrap();
+        assert_eq!(scanner.word().unwrap_err().found, "`,`");
+        scanner.literal(",").unwrap();
+        assert_eq!(scanner.one_of(&["blue", "red"]).unwrap(), "red");
+
+        let mut scanner = LineScanner::new(1, "3X");
+        assert_eq!(scanner.character("a card", |c| c.is_ascii_digit()).unwrap(), '3');
+        assert_eq!(scanner.character("a card", |c| c.is_ascii_digit()).unwrap_err().found, "`X`");
+
+        let mut scanner = LineScanner::new(1, "1 purple");
+        scanner.number::<u32>().unwrap();
+        let error = scanner.one_of(&["red", "green", "blue"]).unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (3, "`red`, `green` or `blue`"));
+    }
+
+    #[test]
+    fn test_render() {
+        let input = "32T3K 765\nT55J5 abc\nKK677 28";
+        let mut scanner = lines(input).nth(1).unwrap();
+        scanner.word().unwrap();
+        let error = scanner.number::<u32>().unwrap_err();
+        assert_eq!(
+            error.render(input),
+            "line 2, column 7: expected a number, found `abc`\n  |\n2 | T55J5 abc\n  |       ^^^\n"
+        );
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        let error = end_of_input("seeds: 79 14", "a blank line");
+        assert_eq!(error.line, 2);
+        assert_eq!(error.render("seeds: 79 14"), "line 2, column 1: expected a blank line, found end of input\n");
+    }
+}
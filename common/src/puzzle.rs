@@ -0,0 +1,170 @@
+use std::any::Any;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Answer, Error, Result, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+impl FromStr for Part {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(Error::Invalid(format!("invalid part `{}`, expected 1 or 2", value))),
+        }
+    }
+}
+
+/// A type-erased [`Solution`], so days can be stored side by side and called generically.
+#[derive(Debug, Clone, Copy)]
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+    parse: fn(&str) -> Result<Box<dyn Any>>,
+    part1: fn(&dyn Any) -> Result<Answer>,
+    part2: fn(&dyn Any) -> Result<Answer>,
+}
+
+/// An input parsed by a [`Puzzle`], ready to be solved by that same puzzle.
+pub struct Parsed {
+    year: u16,
+    day: u8,
+    model: Box<dyn Any>,
+}
+
+impl Puzzle {
+    pub fn of<S: Solution>() -> Self {
+        return Puzzle {
+            year: S::YEAR,
+            day: S::DAY,
+            parse: |input| Ok(Box::new(S::parse(input)?)),
+            part1: |model| S::part1(model.downcast_ref().unwrap()),
+            part2: |model| S::part2(model.downcast_ref().unwrap()),
+        };
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Parsed> {
+        return Ok(Parsed {
+            year: self.year,
+            day: self.day,
+            model: (self.parse)(input)?,
+        });
+    }
+
+    pub fn solve_parsed(&self, part: Part, parsed: &Parsed) -> Result<Answer> {
+        if (parsed.year, parsed.day) != (self.year, self.day) {
+            return Err(Error::Invalid(format!(
+                "input parsed by {} day {:02} cannot be solved by {}",
+                parsed.year, parsed.day, self
+            )));
+        }
+        match part {
+            Part::One => (self.part1)(parsed.model.as_ref()),
+            Part::Two => (self.part2)(parsed.model.as_ref()),
+        }
+    }
+
+    pub fn solve(&self, part: Part, input: &str) -> Result<Answer> {
+        return self.solve_parsed(part, &self.parse(input)?);
+    }
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} day {:02}", self.year, self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Example;
+
+    impl Solution for Example {
+        const YEAR: u16 = 2015;
+        const DAY: u8 = 1;
+
+        type Model = String;
+
+        fn parse(input: &str) -> Result<String> {
+            if input.is_empty() {
+                return Err(Error::Invalid("empty input".to_string()));
+            }
+            return Ok(input.to_string());
+        }
+
+        fn part1(model: &String) -> Result<Answer> {
+            return Ok(model.len().into());
+        }
+
+        fn part2(model: &String) -> Result<Answer> {
+            return Ok(model.to_uppercase().into());
+        }
+    }
+
+    struct Other;
+
+    impl Solution for Other {
+        const YEAR: u16 = 2015;
+        const DAY: u8 = 2;
+
+        type Model = ();
+
+        fn parse(_input: &str) -> Result<()> {
+            return Ok(());
+        }
+
+        fn part1(_model: &()) -> Result<Answer> {
+            return Ok(0.into());
+        }
+
+        fn part2(_model: &()) -> Result<Answer> {
+            return Ok(0.into());
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let puzzle = Puzzle::of::<Example>();
+        assert_eq!(puzzle.to_string(), "2015 day 01");
+        assert!(puzzle.parse("").is_err());
+
+        let parsed = puzzle.parse("abcd").unwrap();
+        assert_eq!(puzzle.solve_parsed(Part::One, &parsed).unwrap(), Answer::Integer(4));
+        assert!(Puzzle::of::<Other>().solve_parsed(Part::One, &parsed).is_err());
+        assert_eq!(puzzle.solve(Part::One, "abc").unwrap(), Answer::Integer(3));
+        assert_eq!(puzzle.solve(Part::Two, "abc").unwrap(), Answer::from("ABC"));
+    }
+
+    #[test]
+    fn test_parse_part() {
+        assert_eq!("1".parse::<Part>().unwrap(), Part::One);
+        assert_eq!("2".parse::<Part>().unwrap(), Part::Two);
+        assert!("3".parse::<Part>().is_err());
+    }
+}
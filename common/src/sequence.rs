@@ -0,0 +1,234 @@
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{CheckedAdd, CheckedSub, One, Signed, Zero};
+
+use crate::{Error, Result};
+
+/// The successive differences of a sequence, down to the first all-zero row.
+///
+/// Works with any integer type with checked arithmetic, including big integers, and reports
+/// overflow as an error instead of wrapping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifferenceTable<T> {
+    rows: Vec<Vec<T>>,
+}
+
+impl<T: Clone + Zero + CheckedAdd + CheckedSub> DifferenceTable<T> {
+    /// Fails on an empty sequence, on overflow, and when the differences never reach an all-zero
+    /// row within the sequence's length, meaning it is not a polynomial of low enough degree.
+    pub fn new(values: &[T]) -> Result<Self> {
+        if values.is_empty() {
+            return Err(Error::Invalid("cannot extrapolate an empty sequence".to_string()));
+        }
+        let mut rows = vec![values.to_vec()];
+        while !rows.last().unwrap().iter().all(T::is_zero) {
+            let row = rows.last().unwrap();
+            if row.len() == 1 {
+                return Err(Error::Invalid(format!(
+                    "the differences of a sequence of {} values never reach zero",
+                    values.len()
+                )));
+            }
+            let differences = row
+                .windows(2)
+                .map(|pair| pair[1].checked_sub(&pair[0]).ok_or_else(overflow))
+                .collect::<Result<Vec<T>>>()?;
+            rows.push(differences);
+        }
+        return Ok(DifferenceTable { rows });
+    }
+
+    /// The degree of the polynomial the sequence follows, with 0 for constant sequences.
+    pub fn degree(&self) -> usize {
+        return self.rows.len().saturating_sub(2);
+    }
+
+    /// The value `steps` places after the last one of the sequence.
+    pub fn forward(&self, steps: usize) -> Result<T> {
+        let mut lasts = self.rows.iter().map(|row| row.last().unwrap().clone()).collect::<Vec<T>>();
+        for _ in 0..steps {
+            for index in (0..lasts.len() - 1).rev() {
+                lasts[index] = lasts[index].checked_add(&lasts[index + 1]).ok_or_else(overflow)?;
+            }
+        }
+        return Ok(lasts.swap_remove(0));
+    }
+
+    /// The value `steps` places before the first one of the sequence.
+    pub fn backward(&self, steps: usize) -> Result<T> {
+        let mut firsts = self.rows.iter().map(|row| row[0].clone()).collect::<Vec<T>>();
+        for _ in 0..steps {
+            for index in (0..firsts.len() - 1).rev() {
+                firsts[index] = firsts[index].checked_sub(&firsts[index + 1]).ok_or_else(overflow)?;
+            }
+        }
+        return Ok(firsts.swap_remove(0));
+    }
+}
+
+impl<T: Clone + Zero + CheckedAdd + CheckedSub + Into<BigInt>> DifferenceTable<T> {
+    /// The polynomial through every value of the sequence, with `n = 0` at the first one.
+    ///
+    /// Built from Newton's forward-difference form `Σ Δᵏf(0) · C(n, k)`, expanded into powers of `n`.
+    pub fn polynomial(&self) -> Polynomial {
+        let mut coefficients = vec![BigRational::zero(); self.rows.len()];
+        // The coefficients of the binomial C(n, k), starting from C(n, 0) = 1.
+        let mut binomial = vec![BigRational::one()];
+        for (k, row) in self.rows.iter().enumerate() {
+            let difference = BigRational::from(row[0].clone().into());
+            for (coefficient, term) in coefficients.iter_mut().zip(&binomial) {
+                *coefficient += &difference * term;
+            }
+
+            let (k, next_k) = (BigRational::from(BigInt::from(k)), BigRational::from(BigInt::from(k + 1)));
+            let mut next = vec![BigRational::zero(); binomial.len() + 1];
+            for (power, term) in binomial.iter().enumerate() {
+                next[power + 1] += term / &next_k;
+                next[power] -= term * &k / &next_k;
+            }
+            binomial = next;
+        }
+        return Polynomial::new(coefficients);
+    }
+}
+
+/// A polynomial in `n` with exact rational coefficients.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polynomial {
+    /// From the constant term up, without trailing zeros.
+    coefficients: Vec<BigRational>,
+}
+
+impl Polynomial {
+    pub fn new(mut coefficients: Vec<BigRational>) -> Self {
+        while coefficients.last().is_some_and(Zero::is_zero) {
+            coefficients.pop();
+        }
+        return Polynomial { coefficients };
+    }
+
+    /// The coefficients from the constant term up, empty for the zero polynomial.
+    pub fn coefficients(&self) -> &[BigRational] {
+        return &self.coefficients;
+    }
+
+    /// The degree, with 0 for constants including zero.
+    pub fn degree(&self) -> usize {
+        return self.coefficients.len().saturating_sub(1);
+    }
+
+    /// The value at `n`, with Horner's method.
+    pub fn evaluate(&self, n: &BigInt) -> BigRational {
+        let n = BigRational::from(n.clone());
+        return self.coefficients.iter().rev().fold(BigRational::zero(), |value, coefficient| value * &n + coefficient);
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.coefficients.is_empty() {
+            return write!(f, "0");
+        }
+        for (power, coefficient) in self.coefficients.iter().enumerate().rev() {
+            if coefficient.is_zero() {
+                continue;
+            }
+            let is_first = power == self.degree();
+            match (is_first, coefficient.is_negative()) {
+                (true, true) => write!(f, "-")?,
+                (false, true) => write!(f, " - ")?,
+                (false, false) => write!(f, " + ")?,
+                (true, false) => {}
+            }
+            let magnitude = coefficient.abs();
+            match power {
+                0 => write!(f, "{}", magnitude)?,
+                _ if magnitude.is_one() => {}
+                _ if magnitude.is_integer() => write!(f, "{}", magnitude)?,
+                _ => write!(f, "{} ", magnitude)?,
+            }
+            match power {
+                0 => {}
+                1 => write!(f, "n")?,
+                _ => write!(f, "n^{}", power)?,
+            }
+        }
+        return Ok(());
+    }
+}
+
+fn overflow() -> Error {
+    return Error::Invalid("the extrapolation overflows its integer type".to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_extrapolate() {
+        let table = DifferenceTable::new(&[10_i64, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(table.degree(), 3);
+        assert_eq!(table.forward(1).unwrap(), 68);
+        assert_eq!(table.backward(1).unwrap(), 5);
+        assert_eq!(table.forward(0).unwrap(), 45);
+
+        let squares = DifferenceTable::new(&[0_i32, 1, 4, 9]).unwrap();
+        assert_eq!(squares.degree(), 2);
+        assert_eq!(squares.forward(6).unwrap(), 81);
+        assert_eq!(squares.backward(3).unwrap(), 9);
+        assert_eq!(DifferenceTable::new(&[7_u8, 7]).unwrap().degree(), 0);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(DifferenceTable::<i64>::new(&[]).is_err());
+        assert!(DifferenceTable::new(&[1_i64, 2, 4, 8]).is_err());
+        assert!(DifferenceTable::new(&[i64::MIN, i64::MAX]).is_err());
+        let table = DifferenceTable::new(&[i64::MAX - 3, i64::MAX - 2, i64::MAX - 1]).unwrap();
+        assert_eq!(table.forward(1).unwrap(), i64::MAX);
+        assert!(table.forward(2).is_err());
+    }
+
+    #[test]
+    fn test_big_integers() {
+        let values = (0..4).map(|n| BigInt::from(u128::MAX) * n * n).collect::<Vec<_>>();
+        let table = DifferenceTable::new(&values).unwrap();
+        assert_eq!(table.forward(1).unwrap(), BigInt::from(u128::MAX) * 16);
+        assert_eq!(table.backward(2).unwrap(), BigInt::from(u128::MAX) * 4);
+    }
+
+    #[test]
+    fn test_polynomial() {
+        let triangular = DifferenceTable::new(&[1_i64, 3, 6, 10, 15, 21]).unwrap().polynomial();
+        assert_eq!(triangular.to_string(), "1/2 n^2 + 3/2 n + 1");
+        assert_eq!(triangular.degree(), 2);
+        assert_eq!(triangular.evaluate(&BigInt::from(-1)), BigRational::zero());
+        let huge = BigInt::from(10).pow(30);
+        let expected = (&huge + 1) * (&huge + 2) / 2;
+        assert_eq!(triangular.evaluate(&huge), BigRational::from(expected));
+
+        let polynomial = DifferenceTable::new(&[10_i64, 13, 16, 21, 30, 45]).unwrap().polynomial();
+        assert_eq!(polynomial.to_string(), "1/3 n^3 - n^2 + 11/3 n + 10");
+        assert_eq!(polynomial.evaluate(&BigInt::from(6)), BigRational::from(BigInt::from(68)));
+        assert_eq!(DifferenceTable::new(&[-3_i32, -5, -7]).unwrap().polynomial().to_string(), "-2n - 3");
+        assert_eq!(DifferenceTable::new(&[0_u8, 0]).unwrap().polynomial().to_string(), "0");
+        assert_eq!(DifferenceTable::new(&[5_u8, 5]).unwrap().polynomial().to_string(), "5");
+    }
+
+    proptest! {
+        #[test]
+        fn test_fit_recovers_coefficients(coefficients in prop::collection::vec(-50_i64..50, 1..6), extra in 1_usize..3) {
+            let expected = Polynomial::new(coefficients.iter().map(|&c| BigRational::from(BigInt::from(c))).collect());
+            let values = (0..coefficients.len() + extra)
+                .map(|n| expected.evaluate(&BigInt::from(n)).to_integer())
+                .collect::<Vec<BigInt>>();
+            let table = DifferenceTable::new(&values).unwrap();
+            prop_assert_eq!(table.degree(), expected.degree());
+            prop_assert_eq!(table.polynomial(), expected);
+        }
+    }
+}
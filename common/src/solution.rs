@@ -0,0 +1,15 @@
+use crate::{Answer, Result};
+
+/// A puzzle solution, identified by its year and day.
+///
+/// The input is parsed once into a [`Solution::Model`], which both parts then solve.
+pub trait Solution {
+    const YEAR: u16;
+    const DAY: u8;
+
+    type Model: 'static;
+
+    fn parse(input: &str) -> Result<Self::Model>;
+    fn part1(model: &Self::Model) -> Result<Answer>;
+    fn part2(model: &Self::Model) -> Result<Answer>;
+}
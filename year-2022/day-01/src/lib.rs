@@ -1,49 +1,69 @@
-use std::fs;
-
-fn part1(input: &str) -> u32 {
-    return input
-        .split("\n\n")
-        .map(|group| {
-            return group
-                .split("\n")
-                .map(|item| item.parse::<u32>().unwrap())
-                .sum::<u32>();
-        })
-        .max()
-        .unwrap();
+use aoc_common::parse::{self, ParseResult};
+use aoc_common::{Answer, Result, Solution};
+
+/// The calories of the items carried by each elf, in input order.
+pub type Inventory = Vec<Vec<u32>>;
+
+pub fn parse(input: &str) -> ParseResult<Inventory> {
+    let mut inventory = vec![Vec::new()];
+    for mut scanner in parse::lines(input) {
+        if scanner.is_at_end() {
+            inventory.push(Vec::new());
+            continue;
+        }
+        let calories = scanner.number::<u32>()?;
+        scanner.end()?;
+        inventory.last_mut().unwrap().push(calories);
+    }
+    return Ok(inventory);
+}
+
+fn get_calories_per_elf(inventory: &Inventory) -> Vec<u32> {
+    return inventory.iter().map(|items| items.iter().sum()).collect();
 }
 
-fn part2(input: &str) -> u32 {
-    let mut results = input
-        .split("\n\n")
-        .map(|group| {
-            return group
-                .split("\n")
-                .map(|item| item.parse::<u32>().unwrap())
-                .sum::<u32>();
-        })
-        .collect::<Vec<u32>>();
+pub fn part1(inventory: &Inventory) -> u32 {
+    return get_calories_per_elf(inventory).into_iter().max().unwrap();
+}
+
+pub fn part2(inventory: &Inventory) -> u32 {
+    let mut results = get_calories_per_elf(inventory);
 
     results.sort_by(|a, b| b.cmp(a));
 
     return results.iter().take(3).sum::<u32>();
 }
 
+pub struct Day01;
+
+impl Solution for Day01 {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 1;
+
+    type Model = Inventory;
+
+    fn parse(input: &str) -> Result<Inventory> {
+        return Ok(parse(input)?);
+    }
+
+    fn part1(inventory: &Inventory) -> Result<Answer> {
+        return Ok(part1(inventory).into());
+    }
+
+    fn part2(inventory: &Inventory) -> Result<Answer> {
+        return Ok(part2(inventory).into());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part1() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part1(&contents), 24000);
-        println!("Part 1: {}", part1(&contents.trim()));
-    }
+    aoc_common::answer_tests!(Day01);
 
     #[test]
-    fn test_part2() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part2(&contents), 45000);
-        println!("Part 2: {}", part2(&contents.trim()));
+    fn test_parse_error() {
+        let error = parse("1000\n2000\n\n3OOO").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (4, 1, "`3OOO`"));
     }
 }
@@ -0,0 +1,442 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use aoc_common::{ParseError, Result};
+
+use crate::vocabulary::{fold_case, Vocabulary};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Numeric,
+    Spelled,
+}
+
+/// A digit read from a line, `length` bytes from the byte offset `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Token {
+    pub start: usize,
+    pub length: usize,
+    pub value: u32,
+    pub kind: TokenKind,
+}
+
+/// The first and last digit of one line, numbered from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Explanation {
+    pub line: usize,
+    pub first: Token,
+    pub last: Token,
+}
+
+impl Explanation {
+    pub fn value(&self) -> u32 {
+        return self.first.value * 10 + self.last.value;
+    }
+
+    /// The numbered line with its value, and the two tokens underlined, on separate rows when they
+    /// overlap.
+    pub fn render(&self, line: &str) -> String {
+        let gutter = self.line.to_string().len();
+        let mut rendered = format!("{} | {}  => {}\n", self.line, line, self.value());
+        let underline = |token: &Token, from: usize| -> String {
+            let padding = line[..token.start].chars().count() - from;
+            let width = line[token.start..token.start + token.length].chars().count();
+            return " ".repeat(padding) + &"^".repeat(width);
+        };
+        let first = underline(&self.first, 0);
+        if self.last == self.first {
+            rendered += &format!("{:gutter$} | {}\n", "", first);
+        } else if self.last.start >= self.first.start + self.first.length {
+            let last = underline(&self.last, line[..self.first.start + self.first.length].chars().count());
+            rendered += &format!("{:gutter$} | {}{}\n", "", first, last);
+        } else {
+            rendered += &format!("{:gutter$} | {}\n{:gutter$} | {}\n", "", first, "", underline(&self.last, 0));
+        }
+        return rendered;
+    }
+}
+
+/// A word of a vocabulary ending at some state of the automaton.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Word {
+    length: usize,
+    value: u32,
+    kind: TokenKind,
+}
+
+impl Word {
+    fn ending_at(&self, end: usize) -> Token {
+        return Token { start: end - self.length, length: self.length, value: self.value, kind: self.kind };
+    }
+}
+
+/// An Aho-Corasick automaton over the words of a [`Vocabulary`].
+///
+/// A single forward pass over the bytes of a line finds every occurrence of every word, overlapping
+/// ones included, so the first and last digit come out without buffering the line.
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    transitions: Vec<[u32; 256]>,
+    /// The longest word ending at each state, which starts earliest.
+    longest: Vec<Option<Word>>,
+    /// The shortest word ending at each state, which starts latest.
+    shortest: Vec<Option<Word>>,
+    /// Whether lines are folded with [`fold_case`] before matching, as the words were.
+    ignore_case: bool,
+    expected: String,
+}
+
+impl Decoder {
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let expected = if vocabulary.words().is_empty() { "a digit" } else { "a digit or the name of a digit" };
+        let mut decoder = Decoder {
+            transitions: vec![[0; 256]],
+            longest: vec![None],
+            shortest: vec![None],
+            ignore_case: vocabulary.ignores_case(),
+            expected: expected.to_string(),
+        };
+        // The trie, with 0 for missing edges since no edge leads back to the root.
+        for (word, value) in vocabulary.keys() {
+            let mut state = 0;
+            for &byte in word.as_bytes() {
+                if decoder.transitions[state][byte as usize] == 0 {
+                    decoder.transitions.push([0; 256]);
+                    decoder.longest.push(None);
+                    decoder.shortest.push(None);
+                    decoder.transitions[state][byte as usize] = decoder.transitions.len() as u32 - 1;
+                }
+                state = decoder.transitions[state][byte as usize] as usize;
+            }
+            let kind = if word.len() == 1 && word.as_bytes()[0].is_ascii_digit() { TokenKind::Numeric } else { TokenKind::Spelled };
+            let word = Word { length: word.len(), value, kind };
+            decoder.longest[state] = Some(word);
+            decoder.shortest[state] = Some(word);
+        }
+
+        // Breadth first, every state inherits the missing edges of the state for its longest proper
+        // suffix, which is always shallower, and the words ending there, which are all shorter.
+        let mut failures = vec![0; decoder.transitions.len()];
+        let mut queue = VecDeque::from([0]);
+        while let Some(state) = queue.pop_front() {
+            let failure = failures[state];
+            if decoder.shortest[failure].is_some() {
+                decoder.shortest[state] = decoder.shortest[failure];
+            }
+            if decoder.longest[state].is_none() {
+                decoder.longest[state] = decoder.longest[failure];
+            }
+            for byte in 0..256 {
+                let child = decoder.transitions[state][byte] as usize;
+                if child == 0 {
+                    decoder.transitions[state][byte] = decoder.transitions[failure][byte];
+                    continue;
+                }
+                failures[child] = if state == 0 { 0 } else { decoder.transitions[failure][byte] as usize };
+                queue.push_back(child);
+            }
+        }
+        return decoder;
+    }
+
+    /// A decoder for the ASCII digits only.
+    pub fn digits() -> Self {
+        return Decoder::new(&Vocabulary::digits());
+    }
+
+    /// A decoder for the ASCII digits and their English names, as the puzzle reads them.
+    pub fn digits_and_names() -> Self {
+        return Decoder::new(&Vocabulary::builtin("english").unwrap());
+    }
+
+    /// The calibration value of one line, or `None` if it has no digit.
+    pub fn decode_line(&self, line: &str) -> Option<u32> {
+        let mut pass = Pass::new(self);
+        pass.feed_line(line.as_bytes());
+        pass.flush();
+        return pass.value();
+    }
+
+    /// The tokens chosen on line number `line_number`, failing if it has no digit.
+    pub fn explain(&self, line_number: usize, line: &str) -> Result<Explanation> {
+        let mut pass = Pass { line_number, ..Pass::new(self) };
+        pass.feed_line(line.as_bytes());
+        pass.flush();
+        let (Some(first), Some(last)) = (pass.first, pass.last) else {
+            return Err(pass.missing_digit().into());
+        };
+        return Ok(Explanation { line: line_number, first, last });
+    }
+
+    /// The tokens chosen on each of `lines`, with an error for every line without a digit.
+    pub fn explain_lines<'a, L>(&'a self, lines: L) -> impl Iterator<Item = Result<Explanation>> + 'a
+    where
+        L: IntoIterator<Item = &'a str>,
+        L::IntoIter: 'a,
+    {
+        return lines.into_iter().enumerate().map(|(index, line)| self.explain(index + 1, line));
+    }
+
+    /// The sum of the calibration values of `lines`, failing on the first line without a digit.
+    pub fn sum_lines<'a>(&self, lines: impl IntoIterator<Item = &'a str>) -> Result<u64> {
+        let mut pass = Pass::new(self);
+        for line in lines {
+            pass.feed_line(line.as_bytes());
+            pass.end_line()?;
+        }
+        return Ok(pass.total);
+    }
+
+    /// The sum of the calibration values of every line read from `reader`, in constant memory.
+    pub fn sum_reader(&self, mut reader: impl BufRead) -> Result<u64> {
+        let mut pass = Pass::new(self);
+        loop {
+            let buffer = reader.fill_buf()?;
+            if buffer.is_empty() {
+                break;
+            }
+            let length = buffer.len();
+            for line in buffer.split_inclusive(|&byte| byte == b'\n') {
+                match line.strip_suffix(b"\n") {
+                    Some(line) => {
+                        pass.feed_line(line);
+                        pass.end_line()?;
+                    }
+                    None => pass.feed_line(line),
+                }
+            }
+            reader.consume(length);
+        }
+        pass.flush();
+        if pass.offset > 0 {
+            pass.end_line()?;
+        }
+        return Ok(pass.total);
+    }
+}
+
+/// The progress of a decoder through a document, fed a line at a time in any number of pieces.
+struct Pass<'a> {
+    decoder: &'a Decoder,
+    state: usize,
+    /// Bytes and characters read on the current line.
+    offset: usize,
+    column: usize,
+    /// Whether the last byte read was a carriage return, left out of the line if a newline follows.
+    pending_return: bool,
+    /// The bytes of a character not yet complete, held back to fold its case.
+    pending_character: Vec<u8>,
+    /// The earliest and latest starting words.
+    first: Option<Token>,
+    last: Option<Token>,
+    line_number: usize,
+    total: u64,
+}
+
+impl<'a> Pass<'a> {
+    fn new(decoder: &'a Decoder) -> Self {
+        return Pass {
+            decoder,
+            state: 0,
+            offset: 0,
+            column: 0,
+            pending_return: false,
+            pending_character: vec![],
+            first: None,
+            last: None,
+            line_number: 1,
+            total: 0,
+        };
+    }
+
+    fn feed_line(&mut self, bytes: &[u8]) {
+        if !self.decoder.ignore_case {
+            bytes.iter().for_each(|&byte| self.step(byte));
+            return;
+        }
+        for &byte in bytes {
+            if byte.is_ascii() {
+                self.flush();
+                self.step(byte.to_ascii_lowercase());
+                continue;
+            }
+            if byte & 0xC0 != 0x80 {
+                self.flush();
+            }
+            self.pending_character.push(byte);
+            match std::str::from_utf8(&self.pending_character) {
+                Ok(character) => {
+                    let folded = fold_case(character.chars().next().unwrap());
+                    self.pending_character.clear();
+                    folded.encode_utf8(&mut [0; 4]).bytes().for_each(|byte| self.step(byte));
+                }
+                Err(error) if error.error_len().is_some() => self.flush(),
+                Err(_) => {}
+            }
+        }
+    }
+
+    /// Reads the bytes of an incomplete character as they are, since they cannot fold.
+    fn flush(&mut self) {
+        for byte in std::mem::take(&mut self.pending_character) {
+            self.step(byte);
+        }
+    }
+
+    fn step(&mut self, byte: u8) {
+        let decoder = self.decoder;
+        self.state = decoder.transitions[self.state][byte as usize] as usize;
+        self.offset += 1;
+        if byte & 0xC0 != 0x80 {
+            self.column += 1;
+        }
+        self.pending_return = byte == b'\r';
+        if let Some(word) = decoder.longest[self.state] {
+            let token = word.ending_at(self.offset);
+            if self.first.is_none_or(|first| token.start < first.start) {
+                self.first = Some(token);
+            }
+        }
+        if let Some(word) = decoder.shortest[self.state] {
+            let token = word.ending_at(self.offset);
+            if self.last.is_none_or(|last| token.start >= last.start) {
+                self.last = Some(token);
+            }
+        }
+    }
+
+    fn value(&self) -> Option<u32> {
+        return Some(self.first?.value * 10 + self.last?.value);
+    }
+
+    /// The error for a line that ended without a digit.
+    fn missing_digit(&self) -> ParseError {
+        return ParseError {
+            line: self.line_number,
+            column: self.column - usize::from(self.pending_return) + 1,
+            expected: self.decoder.expected.clone(),
+            found: "end of line".to_string(),
+        };
+    }
+
+    fn end_line(&mut self) -> Result<()> {
+        self.flush();
+        let Some(value) = self.value() else {
+            return Err(self.missing_digit().into());
+        };
+        self.total += u64::from(value);
+        *self = Pass { total: self.total, line_number: self.line_number + 1, ..Pass::new(self.decoder) };
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vocabulary::ENGLISH;
+    use aoc_common::Error;
+    use proptest::prelude::*;
+    use std::io::BufReader;
+
+    /// Finds the first and last word by searching for each of them, as day 01 used to.
+    fn decode_by_search(line: &str) -> Option<u32> {
+        let digits = (0..10).map(|digit| (digit.to_string(), digit));
+        let words = digits.chain(ENGLISH.iter().map(|&(name, value)| (name.to_string(), value))).collect::<Vec<_>>();
+        let first = words.iter().filter_map(|(word, value)| Some((line.find(word.as_str())?, *value))).min_by_key(|&(index, _)| index)?;
+        let last = words.iter().filter_map(|(word, value)| Some((line.rfind(word.as_str())?, *value))).max_by_key(|&(index, _)| index)?;
+        return Some(first.1 * 10 + last.1);
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        let decoder = Decoder::digits_and_names();
+        assert_eq!(decoder.decode_line("twone"), Some(21));
+        assert_eq!(decoder.decode_line("eightwo"), Some(82));
+        assert_eq!(decoder.decode_line("xtwonex"), Some(21));
+        assert_eq!(decoder.decode_line("sevenine"), Some(79));
+        assert_eq!(decoder.decode_line("abc"), None);
+        assert_eq!(Decoder::digits().decode_line("one2three"), Some(22));
+        assert_eq!(decoder.decode_line("zero"), None);
+        let suffixes = Vocabulary::new([("abcd", 1), ("bc", 2)]).unwrap();
+        assert_eq!(Decoder::new(&suffixes).decode_line("abcd"), Some(12));
+    }
+
+    #[test]
+    fn test_vocabularies() {
+        let german = Decoder::new(&Vocabulary::builtin("german").unwrap());
+        assert_eq!(german.decode_line("xfünfundzwanzig"), Some(55));
+        assert_eq!(german.decode_line("FÜNF3"), Some(33));
+        let german = Decoder::new(&Vocabulary::builtin("german").unwrap().ignoring_case().unwrap());
+        assert_eq!(german.decode_line("FÜNFundZwei"), Some(52));
+        let document = "FÜNFundZwei\nxSECHS\r\nvierÿ\nX\u{212A}Ü8";
+        for capacity in [1, 2, 64] {
+            assert_eq!(german.sum_reader(BufReader::with_capacity(capacity, document.as_bytes())).unwrap(), 52 + 66 + 44 + 88);
+        }
+        assert_eq!(german.sum_reader(b"1\nEINS\xC3".as_slice()).unwrap(), 11 + 11);
+        assert!(german.sum_reader(b"1\n\xC3".as_slice()).is_err());
+        let explanation = german.explain(1, "xFÜNFy").unwrap();
+        assert_eq!(explanation.render("xFÜNFy"), "1 | xFÜNFy  => 55\n  |  ^^^^\n");
+        let french = Decoder::new(&Vocabulary::builtin("french").unwrap());
+        assert_eq!(french.decode_line("dix-huit et un"), Some(81));
+        let spanish = Decoder::new(&Vocabulary::builtin("spanish").unwrap());
+        assert_eq!(spanish.decode_line("cuatrocientos dos"), Some(42));
+    }
+
+    #[test]
+    fn test_long_words_ignoring_case() {
+        let word = "abcdefghijklmnopqrstuvwxyz".repeat(4);
+        let vocabulary = Vocabulary::new([(word.clone(), 4)]).unwrap().ignoring_case().unwrap();
+        let decoder = Decoder::new(&vocabulary);
+        // One state per letter, rather than one per mix of cases.
+        assert_eq!(decoder.transitions.len(), 1 + 10 + word.len());
+        assert_eq!(decoder.decode_line(&format!("x{}", word.to_uppercase())), Some(44));
+    }
+
+    #[test]
+    fn test_sum_reader() {
+        let document = "two1nine\r\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen";
+        let decoder = Decoder::digits_and_names();
+        for capacity in [1, 3, 64] {
+            assert_eq!(decoder.sum_reader(BufReader::with_capacity(capacity, document.as_bytes())).unwrap(), 281);
+        }
+        assert_eq!(decoder.sum_reader(format!("{}\n", document).as_bytes()).unwrap(), 281);
+        assert_eq!(decoder.sum_reader("".as_bytes()).unwrap(), 0);
+
+        let Err(Error::Parse(error)) = decoder.sum_reader("two1nine\nabcxyz\r\nzoneight234".as_bytes()) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 7, "end of line"));
+        let Err(Error::Parse(error)) = decoder.sum_reader("1\n\nééé".as_bytes()) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column), (2, 1));
+        assert!(decoder.sum_reader("1\nééé".as_bytes()).is_err_and(|error| error.to_string().contains("line 2, column 4")));
+    }
+
+    #[test]
+    fn test_explain() {
+        let decoder = Decoder::digits_and_names();
+        let explanation = decoder.explain(3, "abcone2threexyz").unwrap();
+        assert_eq!(explanation.first, Token { start: 3, length: 3, value: 1, kind: TokenKind::Spelled });
+        assert_eq!(explanation.last, Token { start: 7, length: 5, value: 3, kind: TokenKind::Spelled });
+        assert_eq!(explanation.render("abcone2threexyz"), "3 | abcone2threexyz  => 13\n  |    ^^^ ^^^^^\n");
+
+        let explanation = decoder.explain(12, "eightwo").unwrap();
+        assert_eq!(explanation.render("eightwo"), "12 | eightwo  => 82\n   | ^^^^^\n   |     ^^^\n");
+        let explanation = decoder.explain(1, "é7é").unwrap();
+        assert_eq!(explanation.first, Token { start: 2, length: 1, value: 7, kind: TokenKind::Numeric });
+        assert_eq!(explanation.render("é7é"), "1 | é7é  => 77\n  |  ^\n");
+
+        let results = decoder.explain_lines(["two1nine", "abcxyz", "7"]).collect::<Vec<_>>();
+        assert_eq!(results[0].as_ref().unwrap().value(), 29);
+        assert!(results[1].as_ref().is_err_and(|error| error.to_string().starts_with("line 2, column 7:")));
+        assert_eq!(results[2].as_ref().unwrap().value(), 77);
+    }
+
+    proptest! {
+        #[test]
+        fn test_decode_against_search(line in "[0-9a-z]{0,6}((zero|one|two|three|four|five|six|seven|eight|nine)[a-z]{0,3}){0,4}") {
+            prop_assert_eq!(Decoder::digits_and_names().decode_line(&line), decode_by_search(&line));
+        }
+    }
+}
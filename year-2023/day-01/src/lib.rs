@@ -1,83 +1,68 @@
-use std::fs;
+pub mod decoder;
+pub mod vocabulary;
 
-fn part1(input: &str) -> u32 {
-    return input.lines().map(|line| {
-        let first_digit = line.chars().find(|&c| c.is_numeric()).unwrap();
-        let last_digit = line.chars().rfind(|&c| c.is_numeric()).unwrap();
+use aoc_common::parse::ParseResult;
+use aoc_common::{Answer, Result, Solution};
+use decoder::{Decoder, Explanation};
 
-        return format!("{}{}", first_digit, last_digit).parse::<u32>().unwrap();
-    }).sum();
+/// The calibration document, one line per entry.
+pub type Document = Vec<String>;
+
+pub fn parse(input: &str) -> ParseResult<Document> {
+    return Ok(input.lines().map(str::to_string).collect());
 }
 
-fn part2(input: &str) -> u32 {
-    let digits_to_words = [
-        ("one", '1'), ("two", '2'), ("three", '3'), ("four", '4'), ("five", '5'),
-        ("six", '6'), ("seven", '7'), ("eight", '8'), ("nine", '9'), ("zero", '0'),
-    ];
-    return input.lines().map(|line| {
-        let first_digit_numeric = line.chars()
-            .enumerate()
-            .find(|&(_, c)| c.is_numeric());
-        let first_digit_word = digits_to_words
-            .iter()
-            .filter_map(|&(word, value)| {
-                line.find(word).map(|index| (index, value))
-            })
-            .min_by_key(|&(index, _)| index);
-        let first_digit = [first_digit_numeric, first_digit_word]
-            .iter()
-            .filter_map(|&option| option)
-            .min_by_key(|&(index, _)| index)
-            .unwrap()
-            .1;
-
-        let last_digit_numeric = line.chars()
-            .rev()
-            .enumerate()
-            .find(|&(_, c)| c.is_numeric())
-            .map(|(index, c)| (line.len() - index - 1, c));
-        let last_digit_word = digits_to_words
-            .iter()
-            .filter_map(|&(word, value)| {
-                line.rfind(word).map(|index| (index, value))
-            })
-            .max_by_key(|&(index, _)| index);
-        let last_digit = [last_digit_numeric, last_digit_word]
-            .iter()
-            .filter_map(|&option| option)
-            .max_by_key(|&(index, _)| index)
-            .unwrap()
-            .1;
-
-        return format!("{}{}", first_digit, last_digit).parse::<u32>().unwrap();
-    }).sum();
+fn lines(document: &Document) -> impl Iterator<Item = &str> {
+    return document.iter().map(String::as_str);
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub fn part1(document: &Document) -> Result<u64> {
+    return Decoder::digits().sum_lines(lines(document));
+}
 
-    #[test]
-    fn test_part1() {
-        let contents = fs::read_to_string("test-part1.txt").unwrap();
-        assert_eq!(part1(&contents), 142);
+pub fn part2(document: &Document) -> Result<u64> {
+    return Decoder::digits_and_names().sum_lines(lines(document));
+}
+
+/// The tokens part 2 chooses on every line, with an error for each line without a digit.
+pub fn explain_part2(document: &Document) -> Vec<Result<Explanation>> {
+    return Decoder::digits_and_names().explain_lines(lines(document)).collect();
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 1;
+
+    type Model = Document;
+
+    // Lines without digits are only rejected while solving, since part 2 also accepts digit names.
+    fn parse(input: &str) -> Result<Document> {
+        return Ok(parse(input)?);
     }
 
-    #[test]
-    fn test_input_part1() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part1(&contents.trim()), 54388);
+    fn part1(document: &Document) -> Result<Answer> {
+        return Ok(part1(document)?.into());
     }
 
-    #[test]
-    fn test_part2() {
-        let contents = fs::read_to_string("test-part2.txt").unwrap();
-        assert_eq!(part2(&contents), 281);
+    fn part2(document: &Document) -> Result<Answer> {
+        return Ok(part2(document)?.into());
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::Error;
+
+    aoc_common::answer_tests!(Day01);
 
     #[test]
-    fn test_input_part2() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part2(&contents.trim()), 53515);
+    fn test_line_without_digits() {
+        let Err(Error::Parse(error)) = part2(&parse("two1nine\nabcxyz\nzoneight234").unwrap()) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 7, "end of line"));
     }
 }
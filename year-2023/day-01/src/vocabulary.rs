@@ -0,0 +1,183 @@
+use std::fs;
+use std::path::Path;
+
+use aoc_common::parse;
+use aoc_common::{Error, Result};
+
+pub const ENGLISH: [(&str, u32); 9] = [
+    ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5),
+    ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9),
+];
+pub const FRENCH: [(&str, u32); 9] = [
+    ("un", 1), ("deux", 2), ("trois", 3), ("quatre", 4), ("cinq", 5),
+    ("six", 6), ("sept", 7), ("huit", 8), ("neuf", 9),
+];
+pub const GERMAN: [(&str, u32); 9] = [
+    ("eins", 1), ("zwei", 2), ("drei", 3), ("vier", 4), ("fünf", 5),
+    ("sechs", 6), ("sieben", 7), ("acht", 8), ("neun", 9),
+];
+pub const SPANISH: [(&str, u32); 9] = [
+    ("uno", 1), ("dos", 2), ("tres", 3), ("cuatro", 4), ("cinco", 5),
+    ("seis", 6), ("siete", 7), ("ocho", 8), ("nueve", 9),
+];
+
+/// The built-in vocabularies by name.
+pub const LANGUAGES: [(&str, &[(&str, u32)]); 4] =
+    [("english", &ENGLISH), ("french", &FRENCH), ("german", &GERMAN), ("spanish", &SPANISH)];
+
+/// The character matched in place of `c` when case is ignored: its lowercase form when that is a
+/// single character of the same length in UTF-8, so that folding a line keeps its byte offsets,
+/// and `c` itself otherwise.
+pub fn fold_case(c: char) -> char {
+    let mut lowercase = c.to_lowercase();
+    return match (lowercase.next(), lowercase.next()) {
+        (Some(folded), None) if folded.len_utf8() == c.len_utf8() => folded,
+        _ => c,
+    };
+}
+
+/// The words standing for digits besides the ASCII digits themselves, which are always read.
+///
+/// No word may stand for two digits, or start like a word for another digit, since either would
+/// leave the first or last digit of a line undecided.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+    ignore_case: bool,
+}
+
+impl Vocabulary {
+    pub fn new<S: Into<String>>(words: impl IntoIterator<Item = (S, u32)>) -> Result<Self> {
+        let vocabulary = Vocabulary { words: words.into_iter().map(|(word, value)| (word.into(), value)).collect(), ignore_case: false };
+        vocabulary.check()?;
+        return Ok(vocabulary);
+    }
+
+    /// The vocabulary without any words, reading only the ASCII digits.
+    pub fn digits() -> Self {
+        return Vocabulary::default();
+    }
+
+    /// The built-in vocabulary of one of [`LANGUAGES`].
+    pub fn builtin(language: &str) -> Option<Self> {
+        let (_, words) = LANGUAGES.iter().find(|(name, _)| *name == language)?;
+        return Some(Vocabulary::new(words.iter().copied()).unwrap());
+    }
+
+    /// Reads one `word = digit` pair per line, skipping blank lines and `#` comments.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut words = vec![];
+        for mut scanner in parse::lines(text) {
+            if scanner.is_at_end() || scanner.rest().trim_start().starts_with('#') {
+                continue;
+            }
+            let word = scanner.word()?.to_string();
+            scanner.literal("=")?;
+            scanner.skip_whitespace();
+            let value = scanner.character("a digit", |c| c.is_ascii_digit())?.to_digit(10).unwrap();
+            scanner.end()?;
+            words.push((word, value));
+        }
+        return Vocabulary::new(words);
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        return Vocabulary::parse(&fs::read_to_string(path)?);
+    }
+
+    /// The same vocabulary matching words whatever the case of their letters.
+    pub fn ignoring_case(self) -> Result<Self> {
+        let vocabulary = Vocabulary { ignore_case: true, ..self };
+        vocabulary.check()?;
+        return Ok(vocabulary);
+    }
+
+    pub fn words(&self) -> &[(String, u32)] {
+        return &self.words;
+    }
+
+    pub fn ignores_case(&self) -> bool {
+        return self.ignore_case;
+    }
+
+    /// Every word to match with its digit, the ASCII digits included, folded with [`fold_case`]
+    /// when case is ignored.
+    pub fn keys(&self) -> Vec<(String, u32)> {
+        let digits = (0..10).map(|digit| (digit.to_string(), digit));
+        return digits.chain(self.words.iter().map(|(word, value)| (self.fold(word), *value))).collect();
+    }
+
+    fn fold(&self, word: &str) -> String {
+        if self.ignore_case {
+            return word.chars().map(fold_case).collect();
+        }
+        return word.to_string();
+    }
+
+    fn check(&self) -> Result<()> {
+        let words = (0..10).map(|digit| (digit.to_string(), digit)).chain(self.words.iter().cloned()).collect::<Vec<_>>();
+        for (index, (word, value)) in words.iter().enumerate() {
+            if word.is_empty() || *value > 9 {
+                return Err(Error::Invalid(format!("`{}` cannot stand for {}", word, value)));
+            }
+            for (other, other_value) in &words[..index] {
+                let (folded, other_folded) = (self.fold(word), self.fold(other));
+                if value != other_value && (folded.starts_with(&other_folded) || other_folded.starts_with(&folded)) {
+                    return Err(Error::Invalid(format!(
+                        "the vocabulary is ambiguous: `{}` stands for {} but `{}` for {}",
+                        other, other_value, word, value
+                    )));
+                }
+            }
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let vocabulary = Vocabulary::parse("# Dutch\neen = 1\n\n  twee=2\n").unwrap();
+        assert_eq!(vocabulary.words(), [("een".to_string(), 1), ("twee".to_string(), 2)]);
+
+        let Err(Error::Parse(error)) = Vocabulary::parse("een = 1\ntwee = x") else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 8, "`x`"));
+        assert!(Vocabulary::parse("een = 12").is_err());
+    }
+
+    #[test]
+    fn test_ambiguity() {
+        assert!(Vocabulary::new([("one", 1), ("one", 1)]).is_ok());
+        assert!(Vocabulary::new([("one", 1), ("one", 2)]).is_err());
+        assert!(Vocabulary::new([("on", 1), ("one", 2)]).is_err());
+        assert!(Vocabulary::new([("7", 3)]).is_err());
+        assert!(Vocabulary::new([("", 3)]).is_err());
+        assert!(Vocabulary::new([("ten", 10)]).is_err());
+
+        let mixed = Vocabulary::new([("one", 1), ("ONE", 2)]).unwrap();
+        assert!(mixed.ignoring_case().is_err_and(|error| error.to_string().contains("`one` stands for 1 but `ONE` for 2")));
+        for (language, _) in LANGUAGES {
+            assert!(Vocabulary::builtin(language).unwrap().ignoring_case().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_keys() {
+        let vocabulary = Vocabulary::new([("FÜnf", 5)]).unwrap();
+        assert_eq!(vocabulary.keys()[10], ("FÜnf".to_string(), 5));
+        let keys = vocabulary.ignoring_case().unwrap().keys();
+        assert_eq!((keys.len(), &keys[10]), (11, &("fünf".to_string(), 5)));
+        assert_eq!(Vocabulary::digits().keys().len(), 10);
+
+        assert_eq!(fold_case('Ü'), 'ü');
+        assert_eq!(fold_case('7'), '7');
+        // The Kelvin sign lowercases to a one-byte `k`, and `İ` to two characters.
+        assert_eq!(fold_case('\u{212A}'), '\u{212A}');
+        assert_eq!(fold_case('İ'), 'İ');
+    }
+}
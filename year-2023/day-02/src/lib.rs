@@ -1,81 +1,168 @@
-use std::fs;
-
-fn part1(input: &str) -> u32 {
-    const MAX_BALLS_PER_COLOR: [(&str, u32); 3] = [
-        ("red", 12),
-        ("green", 13),
-        ("blue", 14),
-    ];
-    return input.lines().map(|line| {
-        let parts: Vec<&str> = line.split(':').collect();
-        let game_parts: Vec<&str> = parts[0].split_whitespace().collect();
-        let game_id: u32 = game_parts[1].parse().unwrap();
-        let ball_sets: Vec<&str> = parts[1].split(';').collect();
-        for ball_set in ball_sets {
-            let balls: Vec<&str> = ball_set.split(',').collect();
-            for ball in balls {
-                let ball_parts: Vec<&str> = ball.trim().split(' ').collect();
-                let color = ball_parts[1];
-                let count = ball_parts[0].parse::<u32>().unwrap();
-                let max_balls = MAX_BALLS_PER_COLOR.iter().find(|(c, _)| c == &color).unwrap().1;
-                if count > max_balls {
-                    return 0;
-                }
-            }
+pub mod queries;
+
+use std::collections::BTreeMap;
+
+use aoc_common::parse::{self, LineScanner, ParseResult};
+use aoc_common::{Answer, Result, Solution};
+
+/// The number of cubes of each color shown at once, by color name.
+pub type Draw = BTreeMap<String, u32>;
+
+/// The number of cubes of each color in a bag, by color name. Colors it does not list have none.
+pub type Bag = BTreeMap<String, u32>;
+
+/// The bag part 1 asks about.
+pub const PUZZLE_BAG: [(&str, u32); 3] = [("red", 12), ("green", 13), ("blue", 14)];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<Draw>,
+}
+
+/// Reads `count color` pairs separated by `separator` into a map, adding up repeated colors.
+fn counts(
+    scanner: &mut LineScanner,
+    separator: &str,
+    mut pair: impl FnMut(&mut LineScanner) -> ParseResult<(String, u32)>,
+) -> ParseResult<BTreeMap<String, u32>> {
+    let mut counts = BTreeMap::new();
+    loop {
+        let (color, count) = pair(scanner)?;
+        *counts.entry(color).or_insert(0) += count;
+        let mut lookahead = scanner.clone();
+        if lookahead.literal(separator).is_err() {
+            return Ok(counts);
         }
-        return game_id;
-    }).sum();
+        *scanner = lookahead;
+    }
 }
 
-fn part2(input: &str) -> u32 {
-    return input.lines().map(|line| {
-        let parts: Vec<&str> = line.split(':').collect();
-        let ball_sets: Vec<&str> = parts[1].split(';').collect();
-        let mut saved_balls: [(&str, u32); 3] = [
-            ("red", 0),
-            ("green", 0),
-            ("blue", 0),
-        ];
-        for ball_set in ball_sets {
-            let balls: Vec<&str> = ball_set.split(',').collect();
-            for ball in balls.iter() {
-                let ball_parts: Vec<&str> = ball.trim().split(' ').collect();
-                let color = ball_parts[1];
-                let count = ball_parts[0].parse::<u32>().unwrap();
-                if saved_balls.iter().find(|(c, _)| c == &color).unwrap().1 < count {
-                    saved_balls.iter_mut().find(|(c, _)| c == &color).unwrap().1 = count;
-                }
+pub fn parse(input: &str) -> ParseResult<Vec<Game>> {
+    return parse::lines(input).map(|mut scanner| {
+        scanner.literal("Game")?;
+        let id = scanner.number::<u32>()?;
+        scanner.literal(":")?;
+
+        let mut draws = vec![];
+        loop {
+            draws.push(counts(&mut scanner, ",", |scanner| {
+                let count = scanner.number::<u32>()?;
+                return Ok((scanner.word()?.to_string(), count));
+            })?);
+            if scanner.literal(";").is_err() {
+                scanner.end()?;
+                return Ok(Game { id, draws });
             }
         }
-        return saved_balls.iter().map(|(_, count)| count).product::<u32>();
+    }).collect();
+}
+
+/// Reads a bag written as `color=count` pairs separated by commas, such as `red=12,green=13`.
+pub fn parse_bag(text: &str) -> ParseResult<Bag> {
+    let mut scanner = LineScanner::new(1, text);
+    let bag = counts(&mut scanner, ",", |scanner| {
+        let color = scanner.word()?.to_string();
+        scanner.literal("=")?;
+        return Ok((color, scanner.number::<u32>()?));
+    })?;
+    scanner.end()?;
+    return Ok(bag);
+}
+
+/// Writes a bag the way [`parse_bag`] reads it.
+pub fn format_bag(bag: &Bag) -> String {
+    return bag.iter().map(|(color, count)| format!("{}={}", color, count)).collect::<Vec<_>>().join(",");
+}
+
+pub fn puzzle_bag() -> Bag {
+    return PUZZLE_BAG.iter().map(|&(color, count)| (color.to_string(), count)).collect();
+}
+
+/// Whether every draw of `game` could have come out of `bag`.
+pub fn is_possible(game: &Game, bag: &Bag) -> bool {
+    return game.draws.iter().all(|draw| {
+        return draw.iter().all(|(color, &count)| count <= bag.get(color).copied().unwrap_or(0));
+    });
+}
+
+/// The sum of the IDs of the games possible with `bag`.
+pub fn possible_id_sum(games: &[Game], bag: &Bag) -> u32 {
+    return games.iter().filter(|game| is_possible(game, bag)).map(|game| game.id).sum();
+}
+
+/// The smallest bag every draw of `game` could have come out of.
+pub fn minimal_bag(game: &Game) -> Bag {
+    let mut bag = Bag::new();
+    for draw in &game.draws {
+        for (color, &count) in draw {
+            let most = bag.entry(color.clone()).or_insert(0);
+            *most = (*most).max(count);
+        }
+    }
+    return bag;
+}
+
+pub fn part1(games: &[Game]) -> u32 {
+    return possible_id_sum(games, &puzzle_bag());
+}
+
+/// The sum over games of the product of the fewest cubes of each puzzle color, with 0 for colors
+/// a game never shows.
+pub fn part2(games: &[Game]) -> u32 {
+    return games.iter().map(|game| {
+        let bag = minimal_bag(game);
+        return PUZZLE_BAG.iter().map(|(color, _)| bag.get(*color).copied().unwrap_or(0)).product::<u32>();
     }).sum();
 }
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 2;
+
+    type Model = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Vec<Game>> {
+        return Ok(parse(input)?);
+    }
+
+    fn part1(games: &Vec<Game>) -> Result<Answer> {
+        return Ok(part1(games).into());
+    }
+
+    fn part2(games: &Vec<Game>) -> Result<Answer> {
+        return Ok(part2(games).into());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part1() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part1(&contents), 8);
-    }
+    aoc_common::answer_tests!(Day02);
 
     #[test]
-    fn test_input_part1() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part1(&contents.trim()), 2720);
-    }
+    fn test_parse_error() {
+        let error = parse("Game 1: 3 blue, 4 red\nGame 2: 1 blue; purple").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 17, "`purple`"));
+        assert_eq!(error.expected, "a number");
 
-    #[test]
-    fn test_part2() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part2(&contents), 2286);
+        let error = parse_bag("red=12,green").unwrap_err();
+        assert_eq!((error.column, error.expected.as_str()), (13, "`=`"));
     }
 
     #[test]
-    fn test_input_part2() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part2(&contents.trim()), 71535);
+    fn test_any_colors() {
+        let games = parse("Game 1: 3 purple, 1 red, 2 purple; 4 teal\nGame 2: 1 blue").unwrap();
+        assert_eq!(games[0].draws[0], Draw::from([("purple".to_string(), 5), ("red".to_string(), 1)]));
+        assert_eq!(minimal_bag(&games[0]), parse_bag("purple=5,red=1,teal=4").unwrap());
+        assert_eq!(format_bag(&minimal_bag(&games[0])), "purple=5,red=1,teal=4");
+
+        assert_eq!(possible_id_sum(&games, &parse_bag("purple=5, red=1, teal=4").unwrap()), 1);
+        assert_eq!(possible_id_sum(&games, &parse_bag("purple=5,red=1,teal=4,blue=1").unwrap()), 3);
+        assert_eq!(possible_id_sum(&games, &puzzle_bag()), 2);
+        assert_eq!(part2(&games), 0);
     }
-}
\ No newline at end of file
+}
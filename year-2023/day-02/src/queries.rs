@@ -0,0 +1,152 @@
+use std::collections::BTreeSet;
+
+use crate::{minimal_bag, Bag, Draw, Game};
+
+/// The first draw of a game that could not have come out of a bag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation<'a> {
+    pub game: &'a Game,
+    /// The index of the draw in the game, from 0.
+    pub draw_index: usize,
+    pub draw: &'a Draw,
+    /// The colors drawn more often than the bag holds, with the counts drawn and held.
+    pub excess: Vec<(&'a str, u32, u32)>,
+}
+
+/// The games impossible with `bag`, each with the first draw that shows it.
+pub fn infeasible_games<'a>(games: &'a [Game], bag: &Bag) -> Vec<Violation<'a>> {
+    let mut violations = vec![];
+    for game in games {
+        for (draw_index, draw) in game.draws.iter().enumerate() {
+            let excess = draw
+                .iter()
+                .map(|(color, &count)| (color.as_str(), count, bag.get(color).copied().unwrap_or(0)))
+                .filter(|&(_, count, held)| count > held)
+                .collect::<Vec<_>>();
+            if !excess.is_empty() {
+                violations.push(Violation { game, draw_index, draw, excess });
+                break;
+            }
+        }
+    }
+    return violations;
+}
+
+/// The smallest bag every game could have been played with.
+pub fn minimal_bag_of_all(games: &[Game]) -> Bag {
+    let mut bag = Bag::new();
+    for game in games {
+        for (color, count) in minimal_bag(game) {
+            let most = bag.entry(color).or_insert(0);
+            *most = (*most).max(count);
+        }
+    }
+    return bag;
+}
+
+/// The bags holding at most a budget of cubes that make the most games possible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BestBags {
+    pub possible_games: usize,
+    /// Every smallest such bag, listing each color of the games, fewest cubes first. Spare cubes
+    /// of the budget can go anywhere.
+    pub bags: Vec<Bag>,
+}
+
+/// The bags of at most `budget` cubes that make the most of `games` possible.
+///
+/// Tries every combination of the counts games need of each color, so the work grows
+/// exponentially with the number of colors.
+pub fn best_bags(games: &[Game], budget: u32) -> BestBags {
+    let needs = games.iter().map(minimal_bag).collect::<Vec<Bag>>();
+    let colors = minimal_bag_of_all(games).into_keys().collect::<Vec<String>>();
+    let candidates = colors
+        .iter()
+        .map(|color| {
+            let counts = needs.iter().map(|need| need.get(color).copied().unwrap_or(0));
+            return counts.chain([0]).collect::<BTreeSet<u32>>().into_iter().collect::<Vec<u32>>();
+        })
+        .collect::<Vec<_>>();
+
+    let mut best = BestBags { possible_games: 0, bags: vec![] };
+    let mut counts = vec![];
+    search(&needs, &colors, &candidates, u64::from(budget), &mut counts, &mut best);
+    best.bags.sort_by_key(|bag| (bag.values().map(|&count| u64::from(count)).sum::<u64>(), bag.values().copied().collect::<Vec<_>>()));
+    best.bags.dedup();
+    return best;
+}
+
+/// Picks the count of the next color among its candidates within what is left of the budget, and
+/// records each complete bag, shrunk to what its possible games need.
+fn search(
+    needs: &[Bag],
+    colors: &[String],
+    candidates: &[Vec<u32>],
+    budget: u64,
+    counts: &mut Vec<u32>,
+    best: &mut BestBags,
+) {
+    let Some(color_candidates) = candidates.get(counts.len()) else {
+        let bag = colors.iter().cloned().zip(counts.iter().copied()).collect::<Bag>();
+        let possible = needs.iter().filter(|need| need.iter().all(|(color, count)| count <= &bag[color])).collect::<Vec<_>>();
+        if possible.len() < best.possible_games {
+            return;
+        }
+        if possible.len() > best.possible_games {
+            *best = BestBags { possible_games: possible.len(), bags: vec![] };
+        }
+        let shrunk = colors
+            .iter()
+            .map(|color| (color.clone(), possible.iter().map(|need| need.get(color).copied().unwrap_or(0)).max().unwrap_or(0)))
+            .collect();
+        best.bags.push(shrunk);
+        return;
+    };
+    for &count in color_candidates.iter().take_while(|&&count| u64::from(count) <= budget) {
+        counts.push(count);
+        search(needs, colors, candidates, budget - u64::from(count), counts, best);
+        counts.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, parse_bag, puzzle_bag};
+
+    const GAMES: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn test_infeasible_games() {
+        let games = parse(GAMES).unwrap();
+        let violations = infeasible_games(&games, &puzzle_bag());
+        assert_eq!(violations.iter().map(|violation| (violation.game.id, violation.draw_index)).collect::<Vec<_>>(), [(3, 0), (4, 2)]);
+        assert_eq!(violations[0].excess, [("red", 20, 12)]);
+        assert_eq!(violations[1].excess, [("blue", 15, 14), ("red", 14, 12)]);
+        assert_eq!(violations[1].draw, &games[3].draws[2]);
+        assert_eq!(infeasible_games(&games, &parse_bag("red=0").unwrap()).len(), 5);
+    }
+
+    #[test]
+    fn test_minimal_bags() {
+        let games = parse(GAMES).unwrap();
+        assert_eq!(minimal_bag_of_all(&games), parse_bag("red=20,green=13,blue=15").unwrap());
+        assert!(minimal_bag_of_all(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_best_bags() {
+        let games = parse(GAMES).unwrap();
+        assert_eq!(best_bags(&games, 48), BestBags { possible_games: 5, bags: vec![minimal_bag_of_all(&games)] });
+        assert_eq!(best_bags(&games, 47).possible_games, 4);
+
+        // Games 1, 2 and 5 fit in red=6,green=3,blue=6, game 4 needs 14 red and 15 blue.
+        let best = best_bags(&games, 15);
+        assert_eq!(best, BestBags { possible_games: 3, bags: vec![parse_bag("red=6,green=3,blue=6").unwrap()] });
+        assert_eq!(best_bags(&games, 0), BestBags { possible_games: 0, bags: vec![parse_bag("red=0,green=0,blue=0").unwrap()] });
+    }
+}
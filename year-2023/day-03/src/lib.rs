@@ -1,103 +1,107 @@
-use std::fs;
-
-fn get_part_numbers_with_positions(input: &str) -> Vec<(String, (usize, usize))> {
-    let mut part_numbers: Vec<(String, (usize, usize))> = Vec::new();
-
-    input.lines().enumerate().for_each(|(index, line)| {
-        let mut current_index = 0;
-
-        while let Some(relative_number_start_index) = line[current_index..].find(|c: char| c.is_numeric()) {
-            let number_start_index = current_index + relative_number_start_index;
-            let number_string = line[number_start_index..]
-                .chars()
-                .take_while(|c| c.is_numeric())
-                .collect::<String>();
-
-            let horizontal_check_range = number_start_index.saturating_sub(1)..(number_start_index + number_string.len() + 1).min(line.len());
-            let vertical_check_range = index.saturating_sub(1)..=(index + 1).min(input.lines().count());
-
-            let is_adjacent_to_symbol = input.lines()
-                .enumerate()
-                .filter(|&(index, _)| vertical_check_range.contains(&index))
-                .any(|(_, neighbor_line)| {
-                    neighbor_line.get(horizontal_check_range.clone())
-                        .map_or(false, |segment| segment.chars().any(|c| !c.is_numeric() && c != '.'))
-                });
-
-            if is_adjacent_to_symbol {
-                part_numbers.push((number_string.clone(), (index, number_start_index)));
-            }
+use aoc_common::grid::{Grid, Run};
+use aoc_common::parse::{ParseError, ParseResult};
+use aoc_common::{Answer, Result, Solution};
+
+/// A number of the schematic, with the run of digits it was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Number {
+    pub value: i32,
+    pub run: Run,
+}
 
-            current_index = number_start_index + number_string.len();
-        }
-    });
-    return part_numbers;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schematic {
+    pub grid: Grid<char>,
+    pub numbers: Vec<Number>,
 }
 
-fn part1(input: &str) -> i32 {
-    let part_numbers_with_positions = get_part_numbers_with_positions(input);
-    return part_numbers_with_positions
-        .iter()
-        .map(|(number_string, _)| number_string.parse::<i32>().unwrap())
-        .sum();
+pub fn parse(input: &str) -> ParseResult<Schematic> {
+    let grid = Grid::parse(input)?;
+    let numbers = grid
+        .runs(char::is_ascii_digit)
+        .into_iter()
+        .map(|run| {
+            let digits = run.positions().map(|position| grid[position]).collect::<String>();
+            let value = digits.parse::<i32>().map_err(|_| ParseError {
+                line: run.row + 1,
+                column: run.start + 1,
+                expected: "a number in range".to_string(),
+                found: format!("`{}`", digits),
+            })?;
+            return Ok(Number { value, run });
+        })
+        .collect::<ParseResult<Vec<Number>>>()?;
+    return Ok(Schematic { grid, numbers });
 }
 
-fn part2(input: &str) -> i32 {
-    let part_numbers_with_positions = get_part_numbers_with_positions(input);
-    let mut product = 0;
-    input.lines().enumerate().for_each(|(index, line)| {
-        let mut current_index = 0;
+fn is_symbol(cell: &char) -> bool {
+    return !cell.is_ascii_digit() && *cell != '.';
+}
 
-        while let Some(relative_gear_start_index) = line[current_index..].find(|c: char| c == '*') {
-            let gear_start_index = current_index + relative_gear_start_index;
+fn is_part_number(schematic: &Schematic, number: &Number) -> bool {
+    return number
+        .run
+        .positions()
+        .any(|position| schematic.grid.neighbors8(position).any(|neighbor| is_symbol(&schematic.grid[neighbor])));
+}
 
-            let horizontal_check_range = gear_start_index.saturating_sub(1)..=(gear_start_index + 1).min(line.len());
-            let vertical_check_range = index.saturating_sub(1)..=(index + 1).min(input.lines().count());
+pub fn part1(schematic: &Schematic) -> i32 {
+    return schematic.numbers
+        .iter()
+        .filter(|number| is_part_number(schematic, number))
+        .map(|number| number.value)
+        .sum();
+}
 
-            let adjacent_numbers = part_numbers_with_positions
+pub fn part2(schematic: &Schematic) -> i32 {
+    return schematic.grid
+        .find_all(|&cell| cell == '*')
+        .into_iter()
+        .map(|gear| {
+            let adjacent_numbers = schematic.numbers
                 .iter()
-                .filter(|(number_string, (number_index, number_start_index))| {
-                    vertical_check_range.contains(number_index) &&
-                        (horizontal_check_range.contains(number_start_index) || horizontal_check_range.contains(&(number_start_index + number_string.len() - 1)))
-                })
-                .map(|(number_string, _)| number_string.parse::<i32>().unwrap())
+                .filter(|number| number.run.is_adjacent_to(gear))
+                .map(|number| number.value)
                 .collect::<Vec<i32>>();
 
             if adjacent_numbers.len() == 2 {
-                product += adjacent_numbers.iter().product::<i32>();
+                return adjacent_numbers.iter().product::<i32>();
             }
-
-            current_index = gear_start_index + 1;
-        }
-    });
-    return product;
+            return 0;
+        })
+        .sum();
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day03;
 
-    #[test]
-    fn test_part1() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part1(&contents), 4361);
+impl Solution for Day03 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 3;
+
+    type Model = Schematic;
+
+    fn parse(input: &str) -> Result<Schematic> {
+        return Ok(parse(input)?);
     }
 
-    #[test]
-    fn test_input_part1() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part1(&contents.trim()), 527369);
+    fn part1(schematic: &Schematic) -> Result<Answer> {
+        return Ok(part1(schematic).into());
     }
 
-    #[test]
-    fn test_part2() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part2(&contents), 467835);
+    fn part2(schematic: &Schematic) -> Result<Answer> {
+        return Ok(part2(schematic).into());
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_common::answer_tests!(Day03);
 
     #[test]
-    fn test_input_part2() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part2(&contents.trim()), 73074886);
+    fn test_number_out_of_range() {
+        let error = parse("467..114..........\n...*..............\n..35..99999999999.").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (3, 7, "`99999999999`"));
     }
 }
@@ -1,78 +1,110 @@
-use std::fs;
-
-fn get_cards(input: &str) -> Vec<(u32, Vec<u32>, Vec<u32>)> {
-    return input
-        .lines()
-        .map(|line| {
-            let parts: Vec<&str> = line.split(": ").collect();
-            let card_number = parts[0].replace("Card", "").trim().parse::<u32>().unwrap();
-            let numbers: Vec<&str> = parts[1].split(" | ").collect();
-            let group1 = numbers[0].split_whitespace().map(|n| n.parse::<u32>().unwrap()).collect();
-            let group2 = numbers[1].split_whitespace().map(|n| n.parse::<u32>().unwrap()).collect();
-            return (card_number, group1, group2);
+use aoc_common::parse::{self, ParseResult};
+use aoc_common::{Answer, Error, Result, Solution};
+
+/// A scratchcard: its number, its winning numbers and the numbers it has.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    pub winning: Vec<u32>,
+    pub numbers: Vec<u32>,
+}
+
+impl Card {
+    /// How many of the numbers the card has are winning numbers.
+    pub fn matches(&self) -> usize {
+        return self.numbers.iter().filter(|number| self.winning.contains(number)).count();
+    }
+}
+
+pub fn parse(input: &str) -> ParseResult<Vec<Card>> {
+    return parse::lines(input)
+        .map(|mut scanner| {
+            scanner.literal("Card")?;
+            let id = scanner.number::<u32>()?;
+            scanner.literal(":")?;
+            let winning = scanner.numbers::<u32>(Some('|'))?;
+            scanner.literal("|")?;
+            let numbers = scanner.numbers::<u32>(None)?;
+            return Ok(Card { id, winning, numbers });
         })
         .collect();
 }
 
-fn part1(input: &str) -> u32 {
-    return get_cards(input)
-        .iter()
-        .map(|(_, winning_numbers, numbers)| {
-            return numbers.iter().map(|&number| winning_numbers.contains(&number)).filter(|&b| b).count() as u32;
-        })
-        .filter(|&count| count > 0)
-        .map(|count| 2_u32.pow(count - 1))
-        .sum();
+/// The sum of the points of every card, doubling for each match after the first.
+pub fn part1(cards: &[Card]) -> Result<u64> {
+    let mut points = 0_u64;
+    for card in cards.iter().filter(|card| card.matches() > 0) {
+        let card_points = u32::try_from(card.matches() - 1).ok().and_then(|exponent| 2_u64.checked_pow(exponent));
+        points = card_points
+            .and_then(|card_points| points.checked_add(card_points))
+            .ok_or_else(|| Error::Invalid(format!("the points of card {} do not fit in 64 bits", card.id)))?;
+    }
+    return Ok(points);
 }
 
-fn part2(input: &str) -> usize {
-    let mut card_copies = get_cards(input)
-        .iter()
-        .map(|(_, winning_numbers, numbers)| {
-            return (1, numbers.iter().map(|&number| winning_numbers.contains(&number)).filter(|&b| b).count());
-        })
-        .collect::<Vec<(usize, usize)>>();
+pub fn part2(cards: &[Card]) -> Result<usize> {
+    let mut card_copies = cards.iter().map(|card| (1, card.matches())).collect::<Vec<(usize, usize)>>();
 
-    let mut scratchcards = 0;
+    let mut scratchcards = 0_usize;
     for index in 0..card_copies.len() {
-        let (card_number, winning_numbers) = card_copies[index];
-        if winning_numbers > 0 {
-            for i in index+1..=index+winning_numbers {
-                let (other_card_number, other_count) = card_copies[i];
-                card_copies[i] = (other_card_number+card_number, other_count);
-            }
+        let (copies, matches) = card_copies[index];
+        let overflow = || Error::Invalid(format!("the copies of card {} are too many to count", cards[index].id));
+        for (other_copies, _) in card_copies.iter_mut().skip(index + 1).take(matches) {
+            *other_copies = other_copies.checked_add(copies).ok_or_else(overflow)?;
         }
-        scratchcards += card_number;
+        scratchcards = scratchcards.checked_add(copies).ok_or_else(overflow)?;
     }
 
-    return scratchcards;
+    return Ok(scratchcards);
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 4;
+
+    type Model = Vec<Card>;
+
+    fn parse(input: &str) -> Result<Vec<Card>> {
+        return Ok(parse(input)?);
+    }
+
+    fn part1(cards: &Vec<Card>) -> Result<Answer> {
+        return Ok(part1(cards)?.into());
+    }
+
+    fn part2(cards: &Vec<Card>) -> Result<Answer> {
+        return Ok(part2(cards)?.into());
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part1() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part1(&contents), 13);
-    }
+    aoc_common::answer_tests!(Day04);
 
     #[test]
-    fn input_part1() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part1(&contents.trim()), 23673);
+    fn test_parse_error() {
+        let error = parse("Card 1: 41 48 83 | 83 86  6\nCard 2: 13 32 20 16 61 61 30 68 82 17").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 38, "end of line"));
+        assert_eq!(error.expected, "`|`");
     }
 
     #[test]
-    fn test_part2() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part2(&contents), 30);
+    fn test_cards() {
+        let cards = parse("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").unwrap();
+        assert_eq!(cards[0], Card { id: 1, winning: vec![41, 48, 83, 86, 17], numbers: vec![83, 86, 6, 31, 17, 9, 48, 53] });
+        assert_eq!(cards[0].matches(), 4);
     }
 
     #[test]
-    fn input_part2() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part2(&contents.trim()), 12263631);
+    fn test_many_matches() {
+        let numbers = (1..=70).collect::<Vec<u32>>();
+        let card = Card { id: 7, winning: numbers.clone(), numbers };
+        assert!(part1(std::slice::from_ref(&card)).is_err_and(|error| error.to_string().contains("card 7")));
+        let card = Card { numbers: (1..=64).collect(), ..card };
+        assert_eq!(part1(&[card]).unwrap(), 1 << 63);
     }
 }
@@ -1,4 +1,6 @@
-use std::fs;
+use aoc_common::interval::{Interval, IntervalSet, PiecewiseOffsetMap};
+use aoc_common::parse::{self, ParseResult};
+use aoc_common::{Answer, Error, Result, Solution};
 
 const SECTION_TITLES: [&str; 7] = [
     "seed-to-soil",
@@ -10,143 +12,119 @@ const SECTION_TITLES: [&str; 7] = [
     "humidity-to-location",
 ];
 
-fn get_seeds_and_maps_list(input: &str) -> (Vec<i64>, Vec<Vec<&str>>) {
-    let seeds: Vec<i64> = input
-        .lines()
-        .nth(0)
-        .unwrap()
-        .split(':')
-        .nth(1)
-        .unwrap()
-        .trim()
-        .split(' ')
-        .map(|s| s.parse::<i64>().unwrap())
-        .collect();
-
-    let maps_list: Vec<Vec<&str>> = SECTION_TITLES.iter().map(|section_title| {
-        return input
-            .split("\n\n")
-            .find(|section| section.starts_with(section_title))
-            .unwrap()
-            .lines()
-            .skip(1)
-            .collect::<Vec<&str>>();
-    }).collect();
-
-    return (seeds, maps_list);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    /// One map per section, in the order of `SECTION_TITLES`.
+    pub maps: Vec<PiecewiseOffsetMap>,
 }
 
-fn get_intersection_range(seed_range: &(i64, i64), map_range: &(i64, i64)) -> (Option<(i64, i64)>, Vec<(i64, i64)>) {
-    let intersection_start = seed_range.0.max(map_range.0);
-    let intersection_end = (seed_range.0 + seed_range.1).min(map_range.0 + map_range.1);
-    let intersection_length = intersection_end - intersection_start;
+pub fn parse(input: &str) -> ParseResult<Almanac> {
+    let mut lines = parse::lines(input);
 
-    if intersection_length > 0 {
-        let intersection_range = (intersection_start, intersection_length);
-        let mut complementary_ranges: Vec<(i64, i64)> = vec![];
+    let mut scanner = lines.next().ok_or_else(|| parse::end_of_input(input, "`seeds:`"))?;
+    scanner.literal("seeds:")?;
+    let seeds: Vec<i64> = scanner.numbers(None)?;
+    if seeds.is_empty() {
+        return Err(scanner.error("a number"));
+    }
 
-        if seed_range.0 < intersection_start {
-            complementary_ranges.push((seed_range.0, intersection_start - seed_range.0));
-        }
-        if seed_range.0 + seed_range.1 > intersection_end {
-            complementary_ranges.push((intersection_end, seed_range.0 + seed_range.1 - intersection_end));
+    let maps: Vec<PiecewiseOffsetMap> = SECTION_TITLES.iter().map(|section_title| {
+        let title = format!("{} map:", section_title);
+        let mut scanner = lines.find(|scanner| !scanner.is_at_end())
+            .ok_or_else(|| parse::end_of_input(input, &format!("`{}`", title)))?;
+        scanner.literal(&title)?;
+        scanner.end()?;
+
+        let mut map = PiecewiseOffsetMap::new();
+        for mut scanner in lines.by_ref() {
+            if scanner.is_at_end() {
+                break;
+            }
+            scanner.skip_whitespace();
+            let line_start = scanner.clone();
+            let (destination_range_start, source_range_start, range_length): (i64, i64, i64) = (scanner.number()?, scanner.number()?, scanner.number()?);
+            scanner.end()?;
+            let source = Interval::from_length(source_range_start, range_length);
+            let offset = destination_range_start.checked_sub(source_range_start);
+            let (Some(source), Some(offset)) = (source, offset) else {
+                return Err(line_start.error_spanning(line_start.rest().trim_end().len(), "a range that fits in 64 bits"));
+            };
+            map.add_rule(source, offset);
         }
-        return (Some(intersection_range), complementary_ranges);
-    } else {
-        return (None, vec![]);
-    }
+        return Ok(map);
+    }).collect::<ParseResult<_>>()?;
+
+    return Ok(Almanac { seeds, maps });
 }
 
-fn part1(input: &str) -> i64 {
-    let (seeds, maps_list) = get_seeds_and_maps_list(input);
+fn overflow_error() -> Error {
+    return Error::Invalid("a seed is mapped outside of the 64-bit range".to_string());
+}
 
-    return seeds.iter().map(|seed| {
-        let mut seed = seed.clone();
+pub fn part1(almanac: &Almanac) -> Result<i64> {
+    return almanac.seeds
+        .iter()
+        .map(|&seed| almanac.maps.iter().try_fold(seed, |seed, map| map.map_point(seed)))
+        .collect::<Option<Vec<i64>>>()
+        .ok_or_else(overflow_error)
+        .map(|locations| locations.into_iter().min().unwrap());
+}
 
-        maps_list.iter().for_each(|maps| {
-            for line in maps {
-                let numbers: Vec<i64> = line.split_whitespace().map(|s| s.parse().unwrap()).collect();
-                let (destination_range_start, source_range_start, range_length) = (numbers[0], numbers[1], numbers[2]);
+pub fn part2(almanac: &Almanac) -> Result<i64> {
+    let seeds = &almanac.seeds;
+    if !seeds.len().is_multiple_of(2) {
+        return Err(Error::Invalid("seed ranges need a start and a length, found an odd number of values".to_string()));
+    }
 
-                if seed >= source_range_start && seed < source_range_start + range_length {
-                    seed = destination_range_start + (seed - source_range_start);
-                    break;
-                }
-            }
-        });
-        return seed;
-    })
-        .collect::<Vec<i64>>()
+    let seed_ranges = seeds
+        .chunks(2)
+        .map(|chunk| Interval::from_length(chunk[0], chunk[1]))
+        .collect::<Option<IntervalSet<i64>>>()
+        .ok_or_else(overflow_error)?;
+    let locations = almanac.maps
         .iter()
-        .min()
-        .unwrap()
-        .clone();
+        .try_fold(seed_ranges, |ranges, map| map.map(&ranges))
+        .ok_or_else(overflow_error)?;
+    return locations.min().ok_or_else(|| Error::Invalid("every seed range is empty".to_string()));
 }
 
-fn part2(input: &str) -> i64 {
-    let (seeds, maps_list) = get_seeds_and_maps_list(input);
+pub struct Day05;
 
-    let mut seed_ranges: Vec<(i64, i64)> = seeds
-        .chunks(2)
-        .map(|chunk| (chunk[0], chunk[1]))
-        .collect();
-    let mut mapped_seed_ranges: Vec<((i64, i64), i64)> = vec![];
-    let mut unmapped_seed_ranges: Vec<(i64, i64)> = vec![];
-
-    maps_list.iter().for_each(|maps| {
-        maps.iter().for_each(|line| {
-            let numbers: Vec<i64> = line.split_whitespace().map(|s| s.parse().unwrap()).collect();
-            let (destination_range_start, source_range_start, range_length) = (numbers[0], numbers[1], numbers[2]);
-
-            seed_ranges.iter().for_each(|seed_range| {
-                if let (Some(intersection_range), mut complementary_ranges) = get_intersection_range(seed_range, &(source_range_start, range_length)) {
-                    mapped_seed_ranges.push((intersection_range, destination_range_start - source_range_start));
-                    unmapped_seed_ranges.append(&mut complementary_ranges);
-                } else {
-                    unmapped_seed_ranges.push(seed_range.clone());
-                }
-            });
-
-            seed_ranges.clear();
-            seed_ranges.extend(unmapped_seed_ranges.iter().cloned());
-            unmapped_seed_ranges.clear();
-        });
-
-        seed_ranges.extend(
-            mapped_seed_ranges
-                .iter()
-                .map(|((range_start, range_length), offset)| (range_start + offset, *range_length))
-        );
-        mapped_seed_ranges.clear();
-    });
-    return seed_ranges.iter().map(|(range_start, _)| range_start).min().unwrap().clone();
+impl Solution for Day05 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 5;
+
+    type Model = Almanac;
+
+    fn parse(input: &str) -> Result<Almanac> {
+        return Ok(parse(input)?);
+    }
+
+    fn part1(almanac: &Almanac) -> Result<Answer> {
+        return Ok(part1(almanac)?.into());
+    }
+
+    fn part2(almanac: &Almanac) -> Result<Answer> {
+        return Ok(part2(almanac)?.into());
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part1() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part1(&contents), 35);
-    }
+    aoc_common::answer_tests!(Day05);
 
     #[test]
-    fn test_input_part1() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part1(&contents.trim()), 251346198);
-    }
+    fn test_parse_error() {
+        let error = parse("seeds: 79 14\n\nseed-to-soil map:\n50 98\n").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (4, 6, "a number"));
 
-    #[test]
-    fn test_part2() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part2(&contents), 46);
-    }
+        let error = parse("seeds: 79 14\n\nsoil-to-fertilizer map:\n0 15 37").unwrap_err();
+        assert_eq!((error.line, error.found.as_str()), (3, "`soil`"));
 
-    #[test]
-    fn test_input_part2() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part2(&contents.trim()), 72263011);
+        let error = parse("seeds: 79 14\n\nseed-to-soil map:\n  50 9223372036854775800 98").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (4, 3, "`50 9223372036854775800 98`"));
     }
 }
@@ -1,64 +1,185 @@
-use std::fs;
+use std::ops::RangeInclusive;
 
-fn get_time_distance_pairs(input: &str) -> Vec<(i64, i64)> {
-    let lines: Vec<&str> = input.lines().collect();
-    let times: Vec<i64> = lines[0].split_whitespace().skip(1).map(|s| s.parse().unwrap()).collect();
-    let distances: Vec<i64> = lines[1].split_whitespace().skip(1).map(|s| s.parse().unwrap()).collect();
+use aoc_common::parse::{self, LineScanner, ParseResult};
+use aoc_common::{Answer, Error, Result, Solution};
 
-    return times.into_iter().zip(distances.into_iter()).collect();
+fn get_row<'a>(scanner: Option<LineScanner<'a>>, input: &str, title: &str) -> ParseResult<(LineScanner<'a>, Vec<u128>)> {
+    let mut scanner = scanner.ok_or_else(|| parse::end_of_input(input, &format!("`{}`", title)))?;
+    scanner.literal(title)?;
+    let mut row = vec![];
+    while !scanner.is_at_end() {
+        scanner.skip_whitespace();
+        row.push(scanner.number::<u128>()?);
+    }
+    return Ok((scanner, row));
 }
 
-fn get_time_distance_pair(input: &str) -> (i64, i64) {
-    let lines: Vec<&str> = input.lines().collect();
-    let time: i64 = lines[0].split_whitespace().skip(1).collect::<Vec<&str>>().join("").parse().unwrap();
-    let distance: i64 = lines[1].split_whitespace().skip(1).collect::<Vec<&str>>().join("").parse().unwrap();
+fn get_rows(input: &str) -> ParseResult<(Vec<u128>, Vec<u128>)> {
+    let mut lines = parse::lines(input);
+    let (_, times) = get_row(lines.next(), input, "Time:")?;
+    let (scanner, distances) = get_row(lines.next(), input, "Distance:")?;
+    if distances.len() != times.len() {
+        return Err(scanner.error(&format!("{} distances", times.len())));
+    }
+    return Ok((times, distances));
+}
 
-    return (time, distance);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race {
+    pub time: u128,
+    pub distance: u128,
 }
 
-fn part1(input: &str) -> i64 {
-    let time_distance_pairs = get_time_distance_pairs(input);
-    return time_distance_pairs.iter().map(|&(time, distance)| {
-        return (0..time)
-            .map(|t| (time - t) * t > distance)
-            .filter(|&t| t)
-            .count() as i64;
-    }).product();
+pub fn parse(input: &str) -> ParseResult<Vec<Race>> {
+    let (times, distances) = get_rows(input)?;
+    return Ok(times.into_iter().zip(distances).map(|(time, distance)| Race { time, distance }).collect());
 }
 
-fn part2(input: &str) -> i64 {
-    let (time, distance) = get_time_distance_pair(input);
-    return (0..time)
-        .map(|t| (time - t) * t > distance)
-        .filter(|&t| t)
-        .count() as i64;
+/// The single race read by ignoring the spaces between the numbers of each row.
+fn get_kerned_race(races: &[Race]) -> Result<Race> {
+    let [time, distance] = [
+        races.iter().map(|race| race.time.to_string()).collect::<String>(),
+        races.iter().map(|race| race.distance.to_string()).collect::<String>(),
+    ].map(|joined| {
+        return joined.parse::<u128>().map_err(|_| Error::Invalid(format!("`{}` does not fit in 128 bits", joined)));
+    });
+
+    return Ok(Race { time: time?, distance: distance? });
+}
+
+/// Whether holding the button for `hold_time` beats the race record.
+fn wins(race: &Race, hold_time: u128) -> bool {
+    // A product too large for u128 is larger than any record.
+    return (race.time - hold_time).checked_mul(hold_time).is_none_or(|traveled| traveled > race.distance);
+}
+
+/// The hold times that beat the record, solving `(time - t) * t > distance` exactly.
+///
+/// The winning times are the integers strictly between the roots of `t² - time·t + distance`,
+/// found with an integer square root of the discriminant, or by binary search on
+/// `0..=time / 2` where the discriminant does not fit in 128 bits.
+pub fn winning_hold_times(race: &Race) -> Option<RangeInclusive<u128>> {
+    let half_time = race.time / 2;
+    if !wins(race, half_time) {
+        return None;
+    }
+
+    let discriminant = race.time.checked_mul(race.time).zip(race.distance.checked_mul(4))
+        .and_then(|(squared_time, four_distances)| squared_time.checked_sub(four_distances));
+    let mut first = match discriminant {
+        Some(discriminant) => ((race.time - discriminant.isqrt()) / 2).min(half_time),
+        None => {
+            let (mut low, mut high) = (0, half_time);
+            while low < high {
+                let middle = low + (high - low) / 2;
+                if wins(race, middle) {
+                    high = middle;
+                } else {
+                    low = middle + 1;
+                }
+            }
+            low
+        }
+    };
+    // The integer square root is off by at most one from the real root.
+    while !wins(race, first) {
+        first += 1;
+    }
+    while first > 0 && wins(race, first - 1) {
+        first -= 1;
+    }
+    return Some(first..=race.time - first);
+}
+
+fn count_ways_to_win(race: &Race) -> u128 {
+    return winning_hold_times(race).map_or(0, |hold_times| hold_times.end() - hold_times.start() + 1);
+}
+
+pub fn part1(races: &[Race]) -> Result<u128> {
+    return races
+        .iter()
+        .map(count_ways_to_win)
+        .try_fold(1_u128, |product, ways| product.checked_mul(ways))
+        .ok_or_else(|| Error::Invalid("the product of the ways to win does not fit in 128 bits".to_string()));
+}
+
+pub fn part2(races: &[Race]) -> Result<u128> {
+    return Ok(count_ways_to_win(&get_kerned_race(races)?));
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 6;
+
+    type Model = Vec<Race>;
+
+    fn parse(input: &str) -> Result<Vec<Race>> {
+        return Ok(parse(input)?);
+    }
+
+    fn part1(races: &Vec<Race>) -> Result<Answer> {
+        return Ok(part1(races)?.into());
+    }
+
+    fn part2(races: &Vec<Race>) -> Result<Answer> {
+        return Ok(part2(races)?.into());
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
-    #[test]
-    fn test_part_1() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part1(&contents), 288);
+    aoc_common::answer_tests!(Day06);
+
+    fn brute_force_hold_times(race: &Race) -> Vec<u128> {
+        return (0..=race.time).filter(|&t| (race.time - t) * t > race.distance).collect();
     }
 
     #[test]
-    fn test_input_part_1() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part1(&contents.trim()), 625968);
+    fn test_winning_hold_times() {
+        assert_eq!(winning_hold_times(&Race { time: 7, distance: 9 }), Some(2..=5));
+        assert_eq!(winning_hold_times(&Race { time: 30, distance: 200 }), Some(11..=19));
+        assert_eq!(winning_hold_times(&Race { time: 6, distance: 9 }), None);
+        assert_eq!(winning_hold_times(&Race { time: 0, distance: 0 }), None);
     }
 
     #[test]
-    fn test_part_2() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part2(&contents), 71503);
+    fn test_large_races() {
+        for race in [
+            Race { time: u128::MAX, distance: u128::MAX },
+            Race { time: u128::MAX, distance: 12345 },
+            Race { time: 1 << 64, distance: (1 << 126) - 1 },
+            Race { time: 1 << 70, distance: 1 << 100 },
+        ] {
+            let hold_times = winning_hold_times(&race).unwrap();
+            let first = *hold_times.start();
+            assert!(wins(&race, first));
+            assert!(first == 0 || !wins(&race, first - 1));
+            assert_eq!(*hold_times.end(), race.time - first);
+        }
+        assert_eq!(winning_hold_times(&Race { time: 1 << 64, distance: 1 << 126 }), None);
+    }
+
+    proptest! {
+        #[test]
+        fn test_against_brute_force(time in 0_u128..300, distance in 0_u128..25_000) {
+            let race = Race { time, distance };
+            let expected = brute_force_hold_times(&race);
+            let actual = winning_hold_times(&race).map_or(vec![], |hold_times| hold_times.collect());
+            prop_assert_eq!(actual, expected);
+        }
     }
 
     #[test]
-    fn test_input_part_2() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part2(&contents.trim()), 43663323);
+    fn test_parse_error() {
+        let error = parse("Time:      7  15   30\nDistance:  9  40").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 17, "3 distances"));
+
+        let error = parse("Time:      7  15   30\nDistances:  9  40  200").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 1, "`Distances`"));
     }
 }
@@ -0,0 +1,27 @@
+use aoc_2023_day_07::{get_total_winnings, parse, Hand, Rules, JOKER_ORDER, STANDARD_ORDER};
+use aoc_common::Inputs;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Sorting with keys recomputed on every comparison, as day 07 used to.
+fn winnings_with_comparator(hands: &[Hand], rules: &Rules) -> i32 {
+    let mut hands = hands.iter().collect::<Vec<&Hand>>();
+    hands.sort_by_key(|hand| rules.key(&hand.cards).unwrap());
+    return hands.iter().enumerate().map(|(index, hand)| hand.bid * (index as i32 + 1)).sum();
+}
+
+fn sorting(c: &mut Criterion) {
+    let Ok(input) = Inputs::for_day(2023, 7).read("input") else {
+        return;
+    };
+    let hands = parse(&input).unwrap();
+    for (name, rules) in [("part1", Rules::new(STANDARD_ORDER, "")), ("part2", Rules::new(JOKER_ORDER, "J"))] {
+        assert_eq!(winnings_with_comparator(&hands, &rules), get_total_winnings(&hands, &rules).unwrap());
+        let mut group = c.benchmark_group(format!("2023-day-07-{}", name));
+        group.bench_function("comparator keys", |b| b.iter(|| winnings_with_comparator(black_box(&hands), &rules)));
+        group.bench_function("precomputed keys", |b| b.iter(|| get_total_winnings(black_box(&hands), &rules)));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, sorting);
+criterion_main!(benches);
@@ -1,194 +1,274 @@
+use aoc_common::parse::{self, ParseResult};
+use aoc_common::{Answer, Error, Result, Solution};
 use std::collections::HashMap;
-use std::fs;
-use std::cmp::Ordering;
 
-const CARD_STRENGTHS: [(char, i32); 13] = [
-    ('2', 2), ('3', 3), ('4', 4), ('5', 5), ('6', 6),
-    ('7', 7), ('8', 8), ('9', 9), ('T', 10), ('J', 11),
-    ('Q', 12), ('K', 13), ('A', 14)
-];
+/// Every card, from weakest to strongest.
+pub const STANDARD_ORDER: &str = "23456789TJQKA";
+/// The card order of part 2, where jokers are the weakest card.
+pub const JOKER_ORDER: &str = "J23456789TQKA";
 
-fn get_card_strength(card: char) -> i32 {
-    CARD_STRENGTHS.iter().find(|&&(c, _)| c == card).map(|&(_, strength)| strength).unwrap()
+/// How hands are ranked: the strength of each card, and which cards stand in for any other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    card_order: Vec<char>,
+    wildcards: Vec<char>,
 }
 
-fn get_card_strength_with_jokers(card: char) -> i32 {
-    if card == 'J' {
-        return 1;
-    }
-    CARD_STRENGTHS.iter().find(|&&(c, _)| c == card).map(|&(_, strength)| strength).unwrap()
-}
-
-enum HandType {
-    FiveOfAKind,
-    FourOfAKind,
-    FullHouse,
-    ThreeOfAKind,
-    TwoPair,
-    OnePair,
-    HighCard,
-}
+/// The sizes of the groups of equal cards in a hand, largest first, such as `[3, 2]` for a full
+/// house. Comparing signatures compares hand categories.
+pub type Signature = Vec<usize>;
 
-fn get_hand_score(hand_type: HandType) -> i32 {
-    match hand_type {
-        HandType::FiveOfAKind => 7,
-        HandType::FourOfAKind => 6,
-        HandType::FullHouse => 5,
-        HandType::ThreeOfAKind => 4,
-        HandType::TwoPair => 3,
-        HandType::OnePair => 2,
-        HandType::HighCard => 1,
+impl Rules {
+    /// Rules with cards ranked in `card_order`, weakest first, and any of `wildcards` joining the
+    /// largest group of other cards.
+    pub fn new(card_order: &str, wildcards: &str) -> Self {
+        return Rules { card_order: card_order.chars().collect(), wildcards: wildcards.chars().collect() };
     }
-}
 
-fn get_hand_bid_pairs(input: &str) -> Vec<(&str, i32)> {
-    return input
-        .lines()
-        .map(|line| {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            let string_part = parts[0];
-            let number_part = parts[1].parse::<i32>().unwrap();
-            (string_part, number_part)
-        })
-        .collect();
-}
+    pub fn strength(&self, card: char) -> Option<usize> {
+        return self.card_order.iter().position(|&c| c == card);
+    }
 
-fn get_hand_value(hand: &str) -> i32 {
-    let mut card_counts = HashMap::new();
-    hand.chars().for_each(|c| {
-        *card_counts.entry(c).or_insert(0) += 1;
-    });
-    return get_hand_score(determine_hand_type(card_counts));
-}
+    pub fn signature(&self, cards: &str) -> Signature {
+        let mut card_counts = HashMap::new();
+        let mut wildcard_count = 0;
+        for card in cards.chars() {
+            if self.wildcards.contains(&card) {
+                wildcard_count += 1;
+            } else {
+                *card_counts.entry(card).or_insert(0) += 1;
+            }
+        }
 
-fn determine_hand_type(card_counts: HashMap<char, i32>) -> HandType {
-    let mut frequencies = card_counts.values().cloned().collect::<Vec<i32>>();
-    frequencies.sort();
+        let mut signature = card_counts.into_values().collect::<Signature>();
+        signature.sort_by(|a, b| b.cmp(a));
+        match signature.first_mut() {
+            Some(largest) => *largest += wildcard_count,
+            None if wildcard_count > 0 => signature.push(wildcard_count),
+            None => {}
+        }
+        return signature;
+    }
 
-    match frequencies.as_slice() {
-        [1, 1, 1, 1, 1] => HandType::HighCard,
-        [1, 1, 1, 2] => HandType::OnePair,
-        [1, 2, 2] => HandType::TwoPair,
-        [1, 1, 3] => HandType::ThreeOfAKind,
-        [2, 3] => HandType::FullHouse,
-        [1, 4] => HandType::FourOfAKind,
-        [5] => HandType::FiveOfAKind,
-        _ => HandType::HighCard,
+    /// A key ordering hands of the same length by category, then card by card from the first.
+    /// Fails on a card missing from the card order.
+    pub fn key(&self, cards: &str) -> Result<HandKey> {
+        let strengths = cards
+            .chars()
+            .map(|card| self.strength(card).ok_or_else(|| Error::Invalid(format!("`{}` is not a card of the rules", card))))
+            .collect::<Result<Vec<usize>>>()?;
+        if self.packs(strengths.len()) {
+            return Ok(HandKey::packed(&self.signature(cards), &strengths));
+        }
+        return Ok(HandKey::Wide(self.signature(cards), strengths));
     }
-}
 
-fn get_hand_value_with_jokers(hand: &str) -> i32 {
-    let mut card_counts = HashMap::new();
-    hand.chars().for_each(|c| {
-        *card_counts.entry(c).or_insert(0) += 1;
-    });
+    /// Whether every key of a hand of `length` cards fits in a [`HandKey::Packed`]: group sizes
+    /// reach the hand length and strengths stay below the number of cards, four bits each, so keys
+    /// of one length never mix the two variants.
+    fn packs(&self, length: usize) -> bool {
+        return length < 16 && self.card_order.len() <= 16;
+    }
 
-    let joker_count = *card_counts.get(&'J').unwrap_or(&0);
-    let mut best_hand_value = get_hand_value(hand); // Start with the value without jokers
+    pub fn rank(&self, hand: &Hand) -> Result<RankedHand> {
+        return Ok(RankedHand { key: self.key(&hand.cards)?, bid: hand.bid });
+    }
+}
 
-    if joker_count > 0 {
-        for &(card, _) in CARD_STRENGTHS.iter() {
-            if card != 'J' {
-                let mut modified_counts = card_counts.clone();
-                *modified_counts.entry(card).or_insert(0) += joker_count;
-                modified_counts.remove(&'J');
+/// The signature of a hand followed by the strengths of its cards, packed four bits each into a
+/// `u128` when the rules and hand length guarantee every value fits, and kept whole otherwise.
+///
+/// Keys only rank hands of the same length under the same rules: a longer packed key sorts above
+/// any shorter one, and every packed key below every wide one.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandKey {
+    Packed(u128),
+    Wide(Signature, Vec<usize>),
+}
 
-                let simulated_hand_value = get_hand_score(determine_hand_type(modified_counts));
-                best_hand_value = std::cmp::max(best_hand_value, simulated_hand_value);
-            }
-        }
+impl HandKey {
+    fn packed(signature: &[usize], strengths: &[usize]) -> Self {
+        // Signatures are padded with zeros to the hand length, so keys of equal length line up.
+        let nibbles = signature.iter().chain(std::iter::repeat(&0)).take(strengths.len()).chain(strengths);
+        return HandKey::Packed(nibbles.fold(0, |key, &value| key << 4 | value as u128));
     }
+}
 
-    return best_hand_value;
+/// A hand with its precomputed key, ordered by that key.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RankedHand {
+    pub key: HandKey,
+    pub bid: i32,
 }
 
-fn compare_hands(hand1: &str, hand2: &str) -> Ordering {
-    let hand1_value = get_hand_value(hand1);
-    let hand2_value = get_hand_value(hand2);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hand {
+    pub cards: String,
+    pub bid: i32,
+}
 
-    if hand1_value != hand2_value {
-        return hand1_value.cmp(&hand2_value);
-    }
+/// Reads hands of the puzzle's cards.
+pub fn parse(input: &str) -> ParseResult<Vec<Hand>> {
+    return parse_with(input, &Rules::new(STANDARD_ORDER, ""));
+}
 
-    for (card1, card2) in hand1.chars().zip(hand2.chars()) {
-        let strength1 = get_card_strength(card1);
-        let strength2 = get_card_strength(card2);
+/// Reads hands of the cards of `rules`, all as long as the first one.
+pub fn parse_with(input: &str, rules: &Rules) -> ParseResult<Vec<Hand>> {
+    let mut length = None;
+    return parse::lines(input)
+        .map(|mut scanner| {
+            scanner.skip_whitespace();
+            let start = scanner.position();
+            let mut card_count = 0;
+            while length.is_none_or(|length| card_count < length)
+                && (card_count == 0 || scanner.rest().starts_with(|c: char| !c.is_whitespace()))
+            {
+                scanner.character("a card", |c| rules.strength(c).is_some())?;
+                card_count += 1;
+            }
+            if length.is_some_and(|length| card_count < length) {
+                return Err(scanner.error("a card"));
+            }
+            length = Some(card_count);
+            let cards = scanner.line()[start..scanner.position()].to_string();
+            scanner.character("a space", char::is_whitespace)?;
+            let bid = scanner.number::<i32>()?;
+            scanner.end()?;
+            Ok(Hand { cards, bid })
+        })
+        .collect();
+}
 
-        match strength1.cmp(&strength2) {
-            Ordering::Equal => continue,
-            other => return other,
-        }
+/// The sum of each bid times the rank of its hand under `rules`, from 1 for the weakest.
+///
+/// Fails on cards missing from the rules, and on hands of different lengths, which have no
+/// common ranking.
+pub fn get_total_winnings(hands: &[Hand], rules: &Rules) -> Result<i32> {
+    if let Some(hand) = hands.iter().find(|hand| hand.cards.chars().count() != hands[0].cards.chars().count()) {
+        return Err(Error::Invalid(format!("`{}` is not as long as `{}`", hand.cards, hands[0].cards)));
     }
+    let mut hands = hands.iter().map(|hand| rules.rank(hand)).collect::<Result<Vec<RankedHand>>>()?;
+    hands.sort_unstable();
 
-    Ordering::Equal
+    return Ok(hands.iter().enumerate().map(|(index, hand)| {
+        hand.bid * (index as i32 + 1)
+    }).sum());
 }
 
-fn part1(input: &str) -> i32 {
-    let mut hand_bid_pairs = get_hand_bid_pairs(input);
-
-    hand_bid_pairs.sort_by(|&(ref hand1, _), &(ref hand2, _)| {
-        compare_hands(hand1, hand2)
-    });
-
-    return hand_bid_pairs.iter().enumerate().map(|(index, &(_, bid))| {
-        bid * (index as i32 + 1)
-    }).sum();
+pub fn part1(hands: &[Hand]) -> Result<i32> {
+    return get_total_winnings(hands, &Rules::new(STANDARD_ORDER, ""));
 }
 
-fn part2(input: &str) -> i32 {
-    let mut hand_bid_pairs = get_hand_bid_pairs(input);
+pub fn part2(hands: &[Hand]) -> Result<i32> {
+    return get_total_winnings(hands, &Rules::new(JOKER_ORDER, "J"));
+}
 
-    hand_bid_pairs.sort_by(|&(ref hand1, _), &(ref hand2, _)| {
-        let hand1_value = get_hand_value_with_jokers(hand1);
-        let hand2_value = get_hand_value_with_jokers(hand2);
+pub struct Day07;
 
-        if hand1_value != hand2_value {
-            return hand1_value.cmp(&hand2_value);
-        }
+impl Solution for Day07 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 7;
 
-        for (card1, card2) in hand1.chars().zip(hand2.chars()) {
-            let strength1 = get_card_strength_with_jokers(card1);
-            let strength2 = get_card_strength_with_jokers(card2);
+    type Model = Vec<Hand>;
 
-            match strength1.cmp(&strength2) {
-                Ordering::Equal => continue,
-                other => return other,
-            }
-        }
+    fn parse(input: &str) -> Result<Vec<Hand>> {
+        return Ok(parse(input)?);
+    }
 
-        Ordering::Equal
-    });
+    fn part1(hands: &Vec<Hand>) -> Result<Answer> {
+        return Ok(part1(hands)?.into());
+    }
 
-    return hand_bid_pairs.iter().enumerate().map(|(index, &(_, bid))| {
-        bid * (index as i32 + 1)
-    }).sum();
+    fn part2(hands: &Vec<Hand>) -> Result<Answer> {
+        return Ok(part2(hands)?.into());
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    aoc_common::answer_tests!(Day07);
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse("32T3K 765\nT55X5 684").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 4, "`X`"));
+
+        let error = parse("32T3K 765\nKK677").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 6, "a space"));
+
+        // Every hand is as long as the first one.
+        let error = parse("32T3K 765\nKK6772 1").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 6, "a space"));
+        let error = parse("32T3K 765\nKK67 1").unwrap_err();
+        assert_eq!((error.line, error.column, error.expected.as_str()), (2, 5, "a card"));
+    }
+
+    #[test]
+    fn test_custom_cards() {
+        let rules = Rules::new("23456789TJQKABCDE", "");
+        let hands = parse_with("E234 1\n2222 10\nD234 100", &rules).unwrap();
+        assert_eq!(get_total_winnings(&hands, &rules).unwrap(), 2 + 10 * 3 + 100);
+        assert_eq!(parse("E234 1").unwrap_err().found, "`E`");
+
+        let error = parse_with("AB 1", &Rules::new(STANDARD_ORDER, "")).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 2));
+        assert!(get_total_winnings(&hands, &Rules::new(STANDARD_ORDER, "")).is_err_and(|error| error.to_string().contains("`E` is not a card")));
+        assert!(Rules::new(STANDARD_ORDER, "").key("2X").is_err());
+    }
+
+    #[test]
+    fn test_different_lengths() {
+        let hands = [Hand { cards: "AAK".to_string(), bid: 1 }, Hand { cards: "AK".to_string(), bid: 2 }];
+        assert!(get_total_winnings(&hands, &Rules::new(STANDARD_ORDER, "")).is_err_and(|error| error.to_string().contains("`AK` is not as long as `AAK`")));
+    }
+
     #[test]
-    fn test_part1() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part1(&contents), 6440);
+    fn test_signatures() {
+        let jokers = Rules::new(JOKER_ORDER, "J");
+        assert_eq!(jokers.signature("KTJJT"), [4, 1]);
+        assert_eq!(jokers.signature("JJJJJ"), [5]);
+        assert_eq!(Rules::new(STANDARD_ORDER, "").signature("KTJJT"), [2, 2, 1]);
+
+        let wild_twos = Rules::new("3456789TJQKA2", "2");
+        assert_eq!(wild_twos.signature("2233"), [4]);
+        assert!(wild_twos.key("A2").unwrap() > wild_twos.key("KQ").unwrap());
+        assert!(wild_twos.key("2KQ").unwrap() > wild_twos.key("AKQ").unwrap());
+        assert!(wild_twos.key("2Q3").unwrap() > wild_twos.key("Q3Q").unwrap());
+        assert!(wild_twos.key("3333333").unwrap() > wild_twos.key("AAAAAAK").unwrap());
     }
 
     #[test]
-    fn test_input_part1() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part1(&contents.trim()), 250957639);
+    fn test_keys() {
+        let rules = Rules::new(STANDARD_ORDER, "");
+        assert_eq!(rules.key("KK677").unwrap(), HandKey::Packed(0x22100_bb455));
+        assert!(rules.key("QQQJA").unwrap() > rules.key("T55J5").unwrap());
+
+        let long_hand = "AKQJT98765432AKQJ";
+        assert!(matches!(rules.key(long_hand).unwrap(), HandKey::Wide(..)));
+        assert!(rules.key(long_hand).unwrap() < rules.key("AAKQJT98765432AKQ").unwrap());
+        assert!(matches!(Rules::new("23456789TJQKABCDE", "").key("EEEE").unwrap(), HandKey::Wide(..)));
     }
 
     #[test]
-    fn test_part2() {
-        let contents = fs::read_to_string("test.txt").unwrap();
-        assert_eq!(part2(&contents), 5905);
+    fn test_keys_of_one_length_share_a_variant() {
+        let wide_order = Rules::new("23456789TJQKABCDE", "");
+        assert!(matches!(wide_order.key("2222").unwrap(), HandKey::Wide(..)));
+        assert!(wide_order.key("2222").unwrap() > wide_order.key("E234").unwrap());
+        assert!(wide_order.key("E234").unwrap() > wide_order.key("D234").unwrap());
+
+        // Sixteen of a kind has a group of 16, which no longer fits in four bits.
+        let rules = Rules::new(STANDARD_ORDER, "");
+        assert!(matches!(rules.key(&"2".repeat(16)).unwrap(), HandKey::Wide(..)));
+        assert!(rules.key(&"2".repeat(16)).unwrap() > rules.key("AKQJT98765432AKQ").unwrap());
+        assert!(matches!(rules.key("AKQJT98765432AK").unwrap(), HandKey::Packed(..)));
+        assert!(rules.key(&"2".repeat(15)).unwrap() > rules.key("AKQJT98765432AK").unwrap());
     }
 
     #[test]
-    fn test_input_part2() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part2(&contents.trim()), 251515496);
+    fn test_arbitrary_lengths() {
+        let hands = parse("AAK 10\n234 3\nAKQ 7").unwrap();
+        assert_eq!(part1(&hands).unwrap(), 3 + 7 * 2 + 10 * 3);
     }
 }
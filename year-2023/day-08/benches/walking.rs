@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use aoc_2023_day_08::{parse, part1, LEFT};
+use aoc_common::Inputs;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Walks from `AAA` to `ZZZ` over nodes keyed by name, as day 08 used to.
+fn steps_with_hash_map(instructions: &str, nodes: &HashMap<String, (String, String)>) -> i64 {
+    let mut number_of_iterations = 0;
+    let mut current_instruction_index = 0;
+    let mut current_node = "AAA";
+    while current_node != "ZZZ" {
+        let (left, right) = &nodes[current_node];
+        let current_instruction = instructions.chars().nth(current_instruction_index).unwrap();
+        current_node = if current_instruction == 'L' { left } else { right };
+        current_instruction_index = (current_instruction_index + 1) % instructions.len();
+        number_of_iterations += 1;
+    }
+    return number_of_iterations;
+}
+
+fn walking(c: &mut Criterion) {
+    let Ok(input) = Inputs::for_day(2023, 8).read("input") else {
+        return;
+    };
+    let network = parse(&input).unwrap();
+    let instructions = network.instructions.iter().map(|&instruction| if instruction == LEFT { 'L' } else { 'R' }).collect::<String>();
+    let nodes = (0..network.names.len() as u32)
+        .map(|id| {
+            let [left, right] = network.adjacency[id as usize];
+            return (network.name(id).to_string(), (network.name(left).to_string(), network.name(right).to_string()));
+        })
+        .collect::<HashMap<String, (String, String)>>();
+    assert_eq!(steps_with_hash_map(&instructions, &nodes), part1(&network).unwrap());
+
+    let mut group = c.benchmark_group("2023-day-08-part1");
+    group.bench_function("hash map", |b| b.iter(|| steps_with_hash_map(black_box(&instructions), black_box(&nodes))));
+    group.bench_function("interned", |b| b.iter(|| part1(black_box(&network))));
+    group.finish();
+}
+
+criterion_group!(benches, walking);
+criterion_main!(benches);
@@ -1,128 +1,216 @@
-use std::collections::{HashMap};
-use std::fs;
-
-fn parse_input(input: &str) -> (&str, HashMap<&str, (String, String)>) {
-    let instructions = input.lines().nth(0).unwrap();
-    let nodes_string = input.split("\n\n").nth(1).unwrap();
-    let mut nodes_map = HashMap::new();
-    for line in nodes_string.lines() {
-        let parts: Vec<&str> = line.split('=').collect();
-        let node = parts[0].trim();
-        let rest = parts[1].trim().trim_start_matches('(').trim_end_matches(')');
-        let nodes: Vec<&str> = rest.split(',').collect();
-        let left = nodes[0].trim().to_string();
-        let right = nodes[1].trim().to_string();
-        nodes_map.insert(node, (left, right));
-    }
-    return (instructions, nodes_map)
+use aoc_common::graph::{self, Cycle, Hits, Strategy};
+use aoc_common::parse::{self, ParseResult};
+use aoc_common::{Answer, Error, Result, Solution};
+use std::collections::HashMap;
+
+/// The index of the successor an instruction picks: `L` for the left one, `R` for the right one.
+pub const LEFT: u8 = 0;
+pub const RIGHT: u8 = 1;
+
+/// The nodes of the map, interned to dense IDs in order of first appearance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Network {
+    /// The instructions as [`LEFT`] and [`RIGHT`], repeated as often as needed.
+    pub instructions: Vec<u8>,
+    /// The name of each node, indexed by its ID.
+    pub names: Vec<String>,
+    /// The left and right successors of each node, indexed by its ID.
+    pub adjacency: Vec<[u32; 2]>,
+    ids: HashMap<String, u32>,
 }
 
-fn part1(input: &str) -> i32 {
-    let (instructions, nodes) = parse_input(input);
+impl Network {
+    pub fn id(&self, name: &str) -> Option<u32> {
+        return self.ids.get(name).copied();
+    }
 
-    let mut number_of_iterations = 0;
-    let mut current_instruction_index = 0;
-    let mut current_node = "AAA";
+    pub fn name(&self, id: u32) -> &str {
+        return &self.names[id as usize];
+    }
 
-    while current_node != "ZZZ" {
-        let current_instruction = instructions.chars().nth(current_instruction_index).unwrap();
+    /// The node reached from `id` by the instruction at `index`.
+    pub fn next(&self, id: u32, index: usize) -> u32 {
+        return self.adjacency[id as usize][self.instructions[index] as usize];
+    }
+}
+
+pub fn parse(input: &str) -> ParseResult<Network> {
+    let mut lines = parse::lines(input);
 
-        if current_instruction == 'L' {
-            current_node = nodes.get(current_node).unwrap().0.as_str();
-        } else if current_instruction == 'R' {
-            current_node = nodes.get(current_node).unwrap().1.as_str();
+    let mut scanner = lines.next().ok_or_else(|| parse::end_of_input(input, "instructions"))?;
+    let mut instructions = vec![];
+    loop {
+        let instruction = scanner.character("`L` or `R`", |c| c == 'L' || c == 'R')?;
+        instructions.push(if instruction == 'L' { LEFT } else { RIGHT });
+        if scanner.is_at_end() {
+            break;
         }
+    }
+    let mut scanner = lines.next().ok_or_else(|| parse::end_of_input(input, "a blank line"))?;
+    scanner.end()?;
+
+    let mut names = vec![];
+    let mut ids = HashMap::new();
+    let mut intern = |name: &str| -> u32 {
+        return *ids.entry(name.to_string()).or_insert_with(|| {
+            names.push(name.to_string());
+            return names.len() as u32 - 1;
+        });
+    };
+    let mut successors = HashMap::new();
+    let mut references = vec![];
+    for mut scanner in lines {
+        let node = intern(scanner.word()?);
+        scanner.literal("=")?;
+        scanner.literal("(")?;
+        scanner.skip_whitespace();
+        references.push(scanner.clone());
+        let left = intern(scanner.word()?);
+        scanner.literal(",")?;
+        scanner.skip_whitespace();
+        references.push(scanner.clone());
+        let right = intern(scanner.word()?);
+        scanner.literal(")")?;
+        scanner.end()?;
+        successors.insert(node, [left, right]);
+    }
+
+    for reference in references {
+        if !successors.contains_key(&ids[reference.clone().word()?]) {
+            return Err(reference.error("the name of a node"));
+        }
+    }
+    let adjacency = (0..names.len() as u32).map(|id| successors[&id]).collect();
+    return Ok(Network { instructions, names, adjacency, ids });
+}
 
-        current_instruction_index = (current_instruction_index + 1) % instructions.len();
+/// The number of steps from `AAA` to `ZZZ`, failing if the walk repeats a state before reaching it.
+pub fn part1(network: &Network) -> Result<i64> {
+    let [start, end] = ["AAA", "ZZZ"].map(|name| {
+        return network.id(name).ok_or_else(|| Error::Invalid(format!("the network has no node named {}", name)));
+    });
+    let (start, end) = (start?, end?);
+
+    // The walk goes through `(node, instruction index)` states like a ghost's, but stops at `ZZZ`,
+    // so the states seen are kept in a dense table instead of finding the whole `Cycle`.
+    let instruction_count = network.instructions.len();
+    let mut visited = vec![false; network.names.len() * instruction_count];
+    let mut number_of_iterations = 0;
+    let mut current_instruction_index = 0;
+    let mut current_node = start;
+    while current_node != end {
+        let state = current_node as usize * instruction_count + current_instruction_index;
+        if visited[state] {
+            return Err(Error::Invalid("ZZZ cannot be reached from AAA".to_string()));
+        }
+        visited[state] = true;
+        current_node = network.next(current_node, current_instruction_index);
+        current_instruction_index = (current_instruction_index + 1) % instruction_count;
         number_of_iterations += 1;
     }
-    return number_of_iterations;
+    return Ok(number_of_iterations);
 }
 
-fn lcm(first: i64, second: i64) -> i64 {
-    first * second / gcd(first, second)
+/// The walk from `start` through `(node, instruction index)` states until one repeats.
+fn walk_cycle(network: &Network, start: u32) -> Cycle<(u32, usize)> {
+    return Cycle::find((start, 0), |&(node, index)| {
+        return (network.next(node, index), (index + 1) % network.instructions.len());
+    });
 }
 
-fn gcd(first: i64, second: i64) -> i64 {
-    let mut max = first;
-    let mut min = second;
-    if min > max {
-        let val = max;
-        max = min;
-        min = val;
+/// The number of steps until the ghosts starting on every node ending with `A` are all on nodes
+/// ending with `Z` at once, with the strategy that found it.
+pub fn ghost_steps(network: &Network) -> Result<(i64, Strategy)> {
+    let walks = (0..network.names.len() as u32)
+        .filter(|&node| network.name(node).ends_with('A'))
+        .map(|node| walk_cycle(network, node).hits(|&(node, _)| network.name(node).ends_with('Z')))
+        .collect::<Vec<Hits>>();
+
+    if walks.is_empty() {
+        return Err(Error::Invalid("the network has no node ending with A".to_string()));
     }
+    let Some((steps, strategy)) = graph::first_common_hit(&walks)? else {
+        return Err(Error::Invalid("the ghosts are never all on nodes ending with Z at once".to_string()));
+    };
+    let steps = i64::try_from(steps).map_err(|_| Error::Invalid("the number of steps does not fit in 64 bits".to_string()))?;
+    return Ok((steps, strategy));
+}
 
-    loop {
-        let res = max % min;
-        if res == 0 {
-            return min;
-        }
+pub fn part2(network: &Network) -> Result<i64> {
+    return Ok(ghost_steps(network)?.0);
+}
+
+pub struct Day08;
 
-        max = min;
-        min = res;
+impl Solution for Day08 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 8;
+
+    type Model = Network;
+
+    fn parse(input: &str) -> Result<Network> {
+        return Ok(parse(input)?);
+    }
+
+    fn part1(network: &Network) -> Result<Answer> {
+        return Ok(part1(network)?.into());
     }
-}
 
-fn part2(input: &str) -> i64 {
-    let (instructions, nodes) = parse_input(input);
-
-    return nodes
-        .iter()
-        .filter(|(&node, _)| node.ends_with('A'))
-        .map(|(&node, _)| {
-            let mut number_of_iterations = 0;
-            let mut current_instruction_index = 0;
-            let mut current_node = node;
-
-            while !current_node.ends_with('Z') {
-                let current_instruction = instructions.chars().nth(current_instruction_index).unwrap();
-
-                if current_instruction == 'L' {
-                    current_node = nodes.get(current_node).unwrap().0.as_str();
-                } else if current_instruction == 'R' {
-                    current_node = nodes.get(current_node).unwrap().1.as_str();
-                }
-
-                current_instruction_index = (current_instruction_index + 1) % instructions.len();
-                number_of_iterations += 1;
-            }
-            return number_of_iterations;
-        })
-        .reduce(|a, b| lcm(a, b))
-        .unwrap();
+    fn part2(network: &Network) -> Result<Answer> {
+        return Ok(part2(network)?.into());
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test1_part1() {
-        let contents = fs::read_to_string("test-part1-1.txt").unwrap();
-        assert_eq!(part1(&contents), 2);
-    }
+    aoc_common::answer_tests!(Day08);
 
     #[test]
-    fn test2_part1() {
-        let contents = fs::read_to_string("test-part1-2.txt").unwrap();
-        assert_eq!(part1(&contents), 6);
+    fn test_parse_error() {
+        let error = parse("LLX\n\nAAA = (BBB, BBB)").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (1, 3, "`X`"));
+
+        let error = parse("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (4, 13, "`ZZZ`"));
     }
 
     #[test]
-    fn input_part1() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part1(&contents), 13771);
+    fn test_interning() {
+        let network = parse("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap();
+        assert_eq!(network.instructions, [LEFT, LEFT, RIGHT]);
+        assert_eq!(network.names, ["AAA", "BBB", "ZZZ"]);
+        assert_eq!(network.adjacency, [[1, 1], [0, 2], [2, 2]]);
+        assert_eq!((network.id("ZZZ"), network.id("CCC")), (Some(2), None));
+        assert_eq!(network.next(1, 2), 2);
     }
 
     #[test]
-    fn test_part2() {
-        let contents = fs::read_to_string("test-part2.txt").unwrap();
-        assert_eq!(part2(&contents), 6);
+    fn test_ghost_strategies() {
+        let network = parse(&aoc_common::Inputs::for_day(2023, 8).read("example-part2").unwrap()).unwrap();
+        assert_eq!(ghost_steps(&network).unwrap(), (6, Strategy::Crt));
+
+        let network = parse("L\n\n11A = (11Z, 11Z)\n11Z = (11B, 11B)\n11B = (11Z, 11Z)\n22A = (22B, 22B)\n22B = (22Z, 22Z)\n22Z = (22A, 22A)").unwrap();
+        assert_eq!(ghost_steps(&network).unwrap(), (5, Strategy::Crt));
+
+        let network = parse("L\n\n11A = (11Z, 11Z)\n11Z = (XXX, XXX)\n22A = (22Z, 22Z)\n22Z = (XXX, XXX)\nXXX = (XXX, XXX)").unwrap();
+        assert_eq!(ghost_steps(&network).unwrap(), (1, Strategy::Tail));
+
+        let network = parse("L\n\n11A = (11B, 11B)\n11B = (11Z, 11Z)\n11Z = (11B, 11B)\n22A = (22B, 22B)\n22B = (22C, 22C)\n22C = (22Z, 22Z)\n22Z = (22B, 22B)").unwrap();
+        assert_eq!(ghost_steps(&network).unwrap(), (6, Strategy::Lcm));
+
+        let network = parse("L\n\n11A = (11Z, 11Z)\n11Z = (11A, 11A)\n22A = (22A, 22A)").unwrap();
+        assert!(ghost_steps(&network).is_err());
     }
 
     #[test]
-    fn input_part2() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part2(&contents), 13129439557681);
+    fn test_unreachable_end() {
+        let network = parse("LR\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)").unwrap();
+        assert!(part1(&network).is_err_and(|error| error.to_string().contains("ZZZ cannot be reached from AAA")));
+        let network = parse("LR\n\nAAA = (BBB, ZZZ)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)").unwrap();
+        assert!(part1(&network).is_err());
+        let network = parse("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap();
+        assert_eq!(part1(&network).unwrap(), 6);
     }
 }
@@ -1,63 +1,87 @@
-use std::fs;
-
-fn compute_extrapolation<F: Fn(&Vec<Vec<i32>>) -> i32>(line: &str, extrapolate: F) -> i32 {
-    let mut histories: Vec<Vec<i32>> = Vec::new();
-    let initial_numbers: Vec<i32> = line.split_whitespace()
-        .map(|n| n.parse::<i32>().unwrap())
-        .collect();
-    histories.push(initial_numbers);
-
-    while !histories.last().unwrap().iter().all(|&x| x == 0) {
-        let next_history = histories.last().unwrap().windows(2)
-            .map(|w| w[1] - w[0])
-            .collect();
-        histories.push(next_history);
-    }
+use aoc_common::parse::{self, ParseResult};
+use aoc_common::sequence::{DifferenceTable, Polynomial};
+use aoc_common::{Answer, Error, Result, Solution};
+
+/// The recorded values of one sensor, oldest first.
+pub type History = Vec<i64>;
+
+pub fn parse(input: &str) -> ParseResult<Vec<History>> {
+    return parse::lines(input).map(|mut scanner| {
+        let history: History = scanner.numbers(None)?;
+        if history.is_empty() {
+            return Err(scanner.error("a number"));
+        }
+        return Ok(history);
+    }).collect();
+}
+
+fn sum_extrapolations(histories: &[History], extrapolate: impl Fn(&DifferenceTable<i64>) -> Result<i64>) -> Result<i64> {
+    return histories.iter().try_fold(0_i64, |sum, history| {
+        let value = extrapolate(&DifferenceTable::new(history)?)?;
+        return sum.checked_add(value).ok_or_else(|| Error::Invalid("the sum of the extrapolations does not fit in 64 bits".to_string()));
+    });
+}
 
-    return extrapolate(&histories);
+/// The polynomial each history follows, with `n = 0` at its oldest value.
+pub fn fit(history: &History) -> Result<Polynomial> {
+    return Ok(DifferenceTable::new(history)?.polynomial());
 }
 
-fn part1(input: &str) -> i32 {
-    return input.lines().map(|line| {
-        return compute_extrapolation(line, |histories| {
-            return histories.iter().rev().fold(0, |acc, history| acc + history.last().unwrap());
-        });
-    }).sum();
+pub fn part1(histories: &[History]) -> Result<i64> {
+    return sum_extrapolations(histories, |table| table.forward(1));
 }
 
-fn part2(input: &str) -> i32 {
-    return input.lines().map(|line| {
-        return compute_extrapolation(line, |histories| {
-            return histories.iter().rev().fold(0, |acc, history| history.first().unwrap() - acc)
-        });
-    }).sum();
+pub fn part2(histories: &[History]) -> Result<i64> {
+    return sum_extrapolations(histories, |table| table.backward(1));
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const YEAR: u16 = 2023;
+    const DAY: u8 = 9;
+
+    type Model = Vec<History>;
+
+    fn parse(input: &str) -> Result<Vec<History>> {
+        return Ok(parse(input)?);
+    }
+
+    fn part1(histories: &Vec<History>) -> Result<Answer> {
+        return Ok(part1(histories)?.into());
+    }
+
+    fn part2(histories: &Vec<History>) -> Result<Answer> {
+        return Ok(part2(histories)?.into());
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_example_part1() {
-        let contents = fs::read_to_string("example.txt").unwrap();
-        assert_eq!(part1(&contents), 114);
-    }
+    aoc_common::answer_tests!(Day09);
 
     #[test]
-    fn test_input_part1() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part1(&contents.trim()), 1868368343);
+    fn test_parse_error() {
+        let error = parse("0 3 6 9 12 15\n1 3 6 10 15 2l").unwrap_err();
+        assert_eq!((error.line, error.column, error.found.as_str()), (2, 13, "`2l`"));
     }
 
     #[test]
-    fn test_example_part2() {
-        let contents = fs::read_to_string("example.txt").unwrap();
-        assert_eq!(part2(&contents), 2);
+    fn test_fit() {
+        let histories = parse("0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45").unwrap();
+        let formulas = histories.iter().map(|history| fit(history).unwrap().to_string()).collect::<Vec<_>>();
+        assert_eq!(formulas, ["3n", "1/2 n^2 + 3/2 n + 1", "1/3 n^3 - n^2 + 11/3 n + 10"]);
     }
 
     #[test]
-    fn test_input_part2() {
-        let contents = fs::read_to_string("input.txt").unwrap();
-        assert_eq!(part2(&contents.trim()), 1022);
+    fn test_extrapolation_errors() {
+        let histories = parse("0 3 6 9 12 15\n1 2 4 8").unwrap();
+        assert!(part1(&histories).is_err());
+
+        let histories = parse("9223372036854775805 9223372036854775806 9223372036854775807").unwrap();
+        assert!(part1(&histories).is_err());
+        assert_eq!(part2(&histories).unwrap(), 9223372036854775804);
     }
 }